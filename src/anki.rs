@@ -0,0 +1,131 @@
+//! Anki export - turn written chunks into importable front/back notes
+//!
+//! Notes are written as comma-separated rows with the chunk transcript on the
+//! front and a `[sound:...]` reference to its audio on the back.
+
+use crate::manifest::Manifest;
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::Path;
+
+/// A single flashcard row
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnkiNote {
+    pub front: String,
+    pub back: String,
+}
+
+/// Notes for every chunk in `manifest`, with sound paths relative to `csv_dir` when possible
+pub fn notes_from_manifest(
+    manifest: &Manifest,
+    source_dir: &Path,
+    csv_dir: &Path,
+) -> Vec<AnkiNote> {
+    manifest
+        .chunks
+        .iter()
+        .map(|chunk| {
+            let audio = source_dir.join(&chunk.path);
+            let relative = audio.strip_prefix(csv_dir).unwrap_or(&audio);
+            AnkiNote {
+                front: chunk.text.clone(),
+                back: format!("[sound:{}]", relative.display()),
+            }
+        })
+        .collect()
+}
+
+/// Write notes as CSV rows, quoting fields that need it
+pub fn write_anki_csv(path: &Path, notes: &[AnkiNote]) -> Result<()> {
+    let csv: String = notes
+        .iter()
+        .map(|note| {
+            format!(
+                "{},{}\n",
+                escape_field(&note.front),
+                escape_field(&note.back)
+            )
+        })
+        .collect();
+    fs::write(path, csv).with_context(|| format!("Failed to write Anki CSV {:?}", path))
+}
+
+/// RFC 4180 quoting: wrap in quotes and double embedded quotes when needed
+fn escape_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::manifest::{manifest_path, read_manifest, write_manifest, ChunkEntry};
+    use std::path::PathBuf;
+
+    /// Minimal RFC 4180 reader for the two-column rows written above
+    fn parse_csv(csv: &str) -> Vec<Vec<String>> {
+        let mut rows = Vec::new();
+        let (mut row, mut field) = (Vec::new(), String::new());
+        let mut chars = csv.chars().peekable();
+        let mut quoted = false;
+        while let Some(c) = chars.next() {
+            match (c, quoted) {
+                ('"', true) if chars.peek() == Some(&'"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                ('"', _) => quoted = !quoted,
+                (',', false) => row.push(std::mem::take(&mut field)),
+                ('\n', false) => {
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                }
+                _ => field.push(c),
+            }
+        }
+        rows
+    }
+
+    #[test]
+    fn two_chunk_manifest_round_trips_through_csv() {
+        let dir = std::env::temp_dir().join(format!("flowalyzer_anki_{}", std::process::id()));
+        let manifest = Manifest {
+            input: PathBuf::from("talk.wav"),
+            settings_hash: "abc".to_string(),
+            chunks: vec![
+                ChunkEntry {
+                    path: PathBuf::from("chunk_1/processed.wav"),
+                    text: "Hola, \"amigo\"".to_string(),
+                    source_start: 0.0,
+                    source_end: 1.5,
+                },
+                ChunkEntry {
+                    path: PathBuf::from("chunk_2/processed.wav"),
+                    text: "¿Qué tal?".to_string(),
+                    source_start: 1.5,
+                    source_end: 2.5,
+                },
+            ],
+        };
+        let path = manifest_path(&dir.join("talk"), "talk");
+        write_manifest(&path, &manifest).unwrap();
+
+        let loaded = read_manifest(&path).unwrap();
+        let notes = notes_from_manifest(&loaded, &dir.join("talk"), &dir);
+        let csv_path = dir.join("deck.csv");
+        write_anki_csv(&csv_path, &notes).unwrap();
+        let rows = parse_csv(&fs::read_to_string(&csv_path).unwrap());
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            rows,
+            vec![
+                vec!["Hola, \"amigo\"", "[sound:talk/chunk_1/processed.wav]"],
+                vec!["¿Qué tal?", "[sound:talk/chunk_2/processed.wav]"],
+            ]
+        );
+    }
+}
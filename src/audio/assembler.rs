@@ -1,8 +1,22 @@
 use crate::types::{AudioChunk, AudioData};
 
+/// Default crossfade between assembled pieces, in milliseconds
+pub const DEFAULT_CROSSFADE_MS: f64 = 2.0;
+
 /// Pure function to concatenate audio chunks into single continuous audio
-/// Adds a simple crossfade between chunks to prevent clicks
-pub fn assemble_audio(chunks: &[AudioChunk]) -> Option<AudioData> {
+/// Adds a `crossfade_ms` crossfade between chunks to prevent clicks
+///
+/// Positive values use at least 10 samples so very short fades still remove
+/// clicks; zero concatenates the chunks exactly, with no fade at all.
+///
+/// With `join_identical`, a chunk bit-identical to the one before it is butt-joined
+/// without a fade: crossfading a signal into a copy of itself comb-filters the overlap,
+/// and identical repeats already meet without a discontinuity the fade would hide.
+pub fn assemble_audio(
+    chunks: &[AudioChunk],
+    crossfade_ms: f64,
+    join_identical: bool,
+) -> Option<AudioData> {
     if chunks.is_empty() {
         return None;
     }
@@ -13,8 +27,7 @@ pub fn assemble_audio(chunks: &[AudioChunk]) -> Option<AudioData> {
         return None; // Mismatched sample rates
     }
 
-    // Calculate crossfade length (2ms to prevent clicks)
-    let crossfade_samples = ((sample_rate as f64 * 0.002) as usize).max(10);
+    let crossfade_samples = crossfade_samples(sample_rate, crossfade_ms);
 
     // Estimate total size
     let total_samples: usize = chunks.iter().map(|c| c.samples.len()).sum();
@@ -26,9 +39,13 @@ pub fn assemble_audio(chunks: &[AudioChunk]) -> Option<AudioData> {
             assembled.extend_from_slice(&chunk.samples);
         } else {
             // Subsequent chunks: crossfade with previous chunk
-            let overlap_len = crossfade_samples
-                .min(chunk.samples.len())
-                .min(assembled.len());
+            let overlap_len = if join_identical && identical(&chunks[idx - 1], chunk) {
+                0
+            } else {
+                crossfade_samples
+                    .min(chunk.samples.len())
+                    .min(assembled.len())
+            };
 
             if overlap_len > 0 {
                 // Apply crossfade: fade out previous, fade in current
@@ -59,6 +76,24 @@ pub fn assemble_audio(chunks: &[AudioChunk]) -> Option<AudioData> {
     })
 }
 
+/// Samples a `crossfade_ms` fade spans at `sample_rate` (at least 10 when positive)
+pub fn crossfade_samples(sample_rate: u32, crossfade_ms: f64) -> usize {
+    if crossfade_ms > 0.0 {
+        ((sample_rate as f64 * crossfade_ms / 1000.0) as usize).max(10)
+    } else {
+        0
+    }
+}
+
+/// Whether two chunks hold exactly the same sample bits
+fn identical(a: &AudioChunk, b: &AudioChunk) -> bool {
+    a.samples.len() == b.samples.len()
+        && a.samples
+            .iter()
+            .zip(&b.samples)
+            .all(|(x, y)| x.to_bits() == y.to_bits())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,7 +115,7 @@ mod tests {
             },
         ];
 
-        let result = assemble_audio(&chunks);
+        let result = assemble_audio(&chunks, DEFAULT_CROSSFADE_MS, false);
         assert!(result.is_some());
 
         let audio = result.unwrap();
@@ -90,10 +125,63 @@ mod tests {
         assert!(audio.samples.len() > 100);
     }
 
+    #[test]
+    fn test_zero_crossfade_concatenates_exactly() {
+        let chunks = vec![
+            AudioChunk {
+                samples: vec![1.0; 100],
+                sample_rate: 44100,
+                start_time: 0.0,
+                end_time: 0.1,
+            },
+            AudioChunk {
+                samples: vec![0.5; 37],
+                sample_rate: 44100,
+                start_time: 0.1,
+                end_time: 0.2,
+            },
+        ];
+
+        let audio = assemble_audio(&chunks, 0.0, false).unwrap();
+
+        assert_eq!(audio.samples.len(), 137);
+        assert_eq!(audio.samples[99], 1.0);
+        assert_eq!(audio.samples[100], 0.5);
+    }
+
+    #[test]
+    fn test_join_identical_skips_crossfade_only_between_copies() {
+        let chunk = |samples: Vec<f32>| AudioChunk {
+            samples,
+            sample_rate: 44100,
+            start_time: 0.0,
+            end_time: 0.0,
+        };
+        let phrase: Vec<f32> = (0..100).map(|i| (i as f32 * 0.1).sin()).collect();
+        let chunks = vec![
+            chunk(phrase.clone()),
+            chunk(phrase.clone()),
+            chunk(vec![0.0; 100]),
+        ];
+
+        let audio = assemble_audio(&chunks, DEFAULT_CROSSFADE_MS, true).unwrap();
+
+        let overlap = (44100.0 * DEFAULT_CROSSFADE_MS / 1000.0) as usize;
+        assert_eq!(audio.samples.len(), 300 - overlap);
+        assert_eq!(&audio.samples[..100], phrase.as_slice());
+        assert_eq!(&audio.samples[100..200 - overlap], &phrase[..100 - overlap]);
+        // The differing silence still fades in over the second copy's tail
+        let faded = &audio.samples[200 - overlap..200];
+        assert!(faded
+            .iter()
+            .zip(&phrase[100 - overlap..])
+            .any(|(mixed, original)| mixed != original));
+    }
+
     #[test]
     fn test_empty_chunks() {
         let chunks: Vec<AudioChunk> = vec![];
-        let result = assemble_audio(&chunks);
+        let result = assemble_audio(&chunks, DEFAULT_CROSSFADE_MS, false);
         assert!(result.is_none());
     }
 
@@ -114,7 +202,7 @@ mod tests {
             },
         ];
 
-        let result = assemble_audio(&chunks);
+        let result = assemble_audio(&chunks, DEFAULT_CROSSFADE_MS, false);
         assert!(result.is_none()); // Should reject mismatched rates
     }
 }
@@ -1,16 +1,45 @@
 use crate::types::AudioData;
-use anyhow::{Context, Result};
+use anyhow::{bail, ensure, Context, Result};
+use serde::Serialize;
 use std::path::Path;
-use symphonia::core::audio::{AudioBufferRef, Signal};
+use symphonia::core::audio::{AudioBuffer, AudioBufferRef, Signal};
 use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
 use symphonia::core::errors::Error as SymphoniaError;
 use symphonia::core::formats::FormatOptions;
 use symphonia::core::io::MediaSourceStream;
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
+use symphonia::core::sample::Sample;
+
+/// Samples at or above this magnitude are treated as clipped
+const CLIP_LEVEL: f32 = 0.999;
+
+/// How multi-channel frames are folded into a single mono sample
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, clap::ValueEnum)]
+pub enum DownmixMode {
+    /// Mean of all channels
+    #[default]
+    Average,
+    /// First channel only
+    Left,
+    /// Second channel only (first channel for mono sources)
+    Right,
+    /// Per frame, the channel sample with the largest magnitude
+    MaxAbs,
+}
+
+/// Configuration for a decode run
+#[derive(Debug, Clone, Default)]
+pub struct DecodeSettings {
+    pub downmix: DownmixMode,
+    /// Error instead of warning when the stream ends before its declared length
+    pub strict: bool,
+    /// Abort once more than this many seconds have been decoded
+    pub max_duration: Option<f64>,
+}
 
 /// Decode an audio file to raw PCM samples (mono, f32)
-pub fn decode_audio<P: AsRef<Path>>(path: P) -> Result<AudioData> {
+pub fn decode_audio<P: AsRef<Path>>(path: P, settings: &DecodeSettings) -> Result<AudioData> {
     let path = path.as_ref();
 
     // Open the file
@@ -45,6 +74,7 @@ pub fn decode_audio<P: AsRef<Path>>(path: P) -> Result<AudioData> {
         .context("No audio tracks found in file")?;
 
     let track_id = track.id;
+    let expected_frames = track.codec_params.n_frames;
     let sample_rate = track
         .codec_params
         .sample_rate
@@ -67,6 +97,7 @@ pub fn decode_audio<P: AsRef<Path>>(path: P) -> Result<AudioData> {
                 if err.kind() == std::io::ErrorKind::UnexpectedEof =>
             {
                 // End of stream
+                check_complete(all_samples.len() as u64, expected_frames, settings.strict)?;
                 break;
             }
             Err(err) => return Err(err).context("Failed to read packet"),
@@ -83,8 +114,9 @@ pub fn decode_audio<P: AsRef<Path>>(path: P) -> Result<AudioData> {
             .context("Failed to decode audio packet")?;
 
         // Convert to f32 mono samples
-        let mono_samples = convert_to_mono_f32(&decoded);
+        let mono_samples = convert_to_mono_f32(&decoded, settings.downmix);
         all_samples.extend(mono_samples);
+        check_duration_cap(all_samples.len(), sample_rate, settings.max_duration)?;
     }
 
     Ok(AudioData {
@@ -93,176 +125,219 @@ pub fn decode_audio<P: AsRef<Path>>(path: P) -> Result<AudioData> {
     })
 }
 
-/// Convert any audio buffer format to mono f32 samples in [-1.0, 1.0]
-fn convert_to_mono_f32(buffer: &AudioBufferRef) -> Vec<f32> {
-    // Get the number of channels and frames
-    let spec = buffer.spec();
-    let num_channels = spec.channels.count();
-    let duration = buffer.frames();
+/// Stop decoding as soon as the decoded length passes `max_duration` seconds
+fn check_duration_cap(decoded: usize, sample_rate: u32, max_duration: Option<f64>) -> Result<()> {
+    if let Some(max) = max_duration {
+        let seconds = decoded as f64 / sample_rate as f64;
+        ensure!(
+            seconds <= max,
+            "Input exceeds the maximum duration of {:.1} minutes; raise --max-duration-minutes to process it",
+            max / 60.0
+        );
+    }
+    Ok(())
+}
 
-    let mut mono_samples = Vec::with_capacity(duration);
+/// Flag streams that end before the frame count their container declared
+fn check_complete(decoded: u64, expected: Option<u64>, strict: bool) -> Result<()> {
+    let Some(expected) = expected.filter(|&expected| decoded < expected) else {
+        return Ok(());
+    };
+    if strict {
+        bail!(
+            "Audio stream ended early: decoded {} of {} declared frames (file may be truncated or corrupt)",
+            decoded,
+            expected
+        );
+    }
+    eprintln!(
+        "   Warning: audio stream ended early, decoded {} of {} declared frames",
+        decoded, expected
+    );
+    Ok(())
+}
 
-    // Convert based on the actual sample format
+/// Convert any audio buffer format to mono f32 samples in [-1.0, 1.0]
+fn convert_to_mono_f32(buffer: &AudioBufferRef, mode: DownmixMode) -> Vec<f32> {
     match buffer {
-        AudioBufferRef::S8(buf) => {
-            // Convert i8 to f32 (normalize to [-1.0, 1.0])
-            let scale = 1.0 / 128.0;
-            if num_channels == 1 {
-                mono_samples.extend(buf.chan(0).iter().map(|&s| s as f32 * scale));
-            } else {
-                for i in 0..duration {
-                    let mut sum = 0.0;
-                    for ch in 0..num_channels {
-                        sum += buf.chan(ch)[i] as f32;
-                    }
-                    mono_samples.push((sum / num_channels as f32) * scale);
-                }
-            }
-        }
-        AudioBufferRef::F32(buf) => {
-            // Already f32, just mix to mono
-            if num_channels == 1 {
-                // Already mono
-                mono_samples.extend_from_slice(buf.chan(0));
-            } else {
-                // Mix all channels to mono by averaging
-                for i in 0..duration {
-                    let mut sum = 0.0;
-                    for ch in 0..num_channels {
-                        sum += buf.chan(ch)[i];
-                    }
-                    mono_samples.push(sum / num_channels as f32);
-                }
-            }
-        }
-        AudioBufferRef::F64(buf) => {
-            // Convert f64 to f32 and mix to mono
-            if num_channels == 1 {
-                mono_samples.extend(buf.chan(0).iter().map(|&s| s as f32));
-            } else {
-                for i in 0..duration {
-                    let mut sum = 0.0;
-                    for ch in 0..num_channels {
-                        sum += buf.chan(ch)[i];
-                    }
-                    mono_samples.push((sum / num_channels as f64) as f32);
-                }
-            }
-        }
-        AudioBufferRef::S16(buf) => {
-            // Convert i16 to f32 (normalize to [-1.0, 1.0])
-            let scale = 1.0 / 32768.0;
-            if num_channels == 1 {
-                mono_samples.extend(buf.chan(0).iter().map(|&s| s as f32 * scale));
-            } else {
-                for i in 0..duration {
-                    let mut sum = 0.0;
-                    for ch in 0..num_channels {
-                        sum += buf.chan(ch)[i] as f32;
-                    }
-                    mono_samples.push((sum / num_channels as f32) * scale);
-                }
-            }
-        }
-        AudioBufferRef::S24(buf) => {
-            // Convert i24 to f32 (normalize to [-1.0, 1.0])
-            let scale = 1.0 / 8388608.0; // 2^23
-            if num_channels == 1 {
-                mono_samples.extend(buf.chan(0).iter().map(|&s| s.inner() as f32 * scale));
-            } else {
-                for i in 0..duration {
-                    let mut sum = 0.0;
-                    for ch in 0..num_channels {
-                        sum += buf.chan(ch)[i].inner() as f32;
-                    }
-                    mono_samples.push((sum / num_channels as f32) * scale);
-                }
-            }
-        }
-        AudioBufferRef::S32(buf) => {
-            // Convert i32 to f32 (normalize to [-1.0, 1.0])
-            let scale = 1.0 / 2147483648.0; // 2^31
-            if num_channels == 1 {
-                mono_samples.extend(buf.chan(0).iter().map(|&s| s as f32 * scale));
-            } else {
-                for i in 0..duration {
-                    let mut sum = 0.0;
-                    for ch in 0..num_channels {
-                        sum += buf.chan(ch)[i] as f32;
-                    }
-                    mono_samples.push((sum / num_channels as f32) * scale);
-                }
-            }
-        }
-        AudioBufferRef::U8(buf) => {
-            // Convert u8 to f32 (normalize to [-1.0, 1.0])
-            let scale = 1.0 / 128.0;
-            let offset = -1.0;
-            if num_channels == 1 {
-                mono_samples.extend(buf.chan(0).iter().map(|&s| s as f32 * scale + offset));
-            } else {
-                for i in 0..duration {
-                    let mut sum = 0.0;
-                    for ch in 0..num_channels {
-                        sum += buf.chan(ch)[i] as f32;
-                    }
-                    mono_samples.push((sum / num_channels as f32) * scale + offset);
-                }
-            }
-        }
-        AudioBufferRef::U16(buf) => {
-            // Convert u16 to f32 (normalize to [-1.0, 1.0])
-            let scale = 1.0 / 32768.0;
-            let offset = -1.0;
-            if num_channels == 1 {
-                mono_samples.extend(buf.chan(0).iter().map(|&s| s as f32 * scale + offset));
-            } else {
-                for i in 0..duration {
-                    let mut sum = 0.0;
-                    for ch in 0..num_channels {
-                        sum += buf.chan(ch)[i] as f32;
-                    }
-                    mono_samples.push((sum / num_channels as f32) * scale + offset);
-                }
-            }
+        AudioBufferRef::S8(buf) => downmix(buf, mode, |s| s as f32 / 128.0),
+        AudioBufferRef::F32(buf) => downmix(buf, mode, |s| s),
+        AudioBufferRef::F64(buf) => downmix(buf, mode, |s| s as f32),
+        AudioBufferRef::S16(buf) => downmix(buf, mode, |s| s as f32 / 32768.0),
+        AudioBufferRef::S24(buf) => downmix(buf, mode, |s| s.inner() as f32 / 8388608.0),
+        AudioBufferRef::S32(buf) => downmix(buf, mode, |s| s as f32 / 2147483648.0),
+        AudioBufferRef::U8(buf) => downmix(buf, mode, |s| s as f32 / 128.0 - 1.0),
+        AudioBufferRef::U16(buf) => downmix(buf, mode, |s| s as f32 / 32768.0 - 1.0),
+        AudioBufferRef::U24(buf) => downmix(buf, mode, |s| s.inner() as f32 / 8388608.0 - 1.0),
+        AudioBufferRef::U32(buf) => downmix(buf, mode, |s| s as f32 / 2147483648.0 - 1.0),
+    }
+}
+
+/// Normalize each channel with `to_f32` and fold the frame into one sample
+fn downmix<S: Sample>(
+    buf: &AudioBuffer<S>,
+    mode: DownmixMode,
+    to_f32: impl Fn(S) -> f32,
+) -> Vec<f32> {
+    let num_channels = buf.spec().channels.count();
+    let mut frame = Vec::with_capacity(num_channels);
+    (0..buf.frames())
+        .map(|i| {
+            frame.clear();
+            frame.extend((0..num_channels).map(|ch| to_f32(buf.chan(ch)[i])));
+            mix_frame(&frame, mode)
+        })
+        .collect()
+}
+
+fn mix_frame(frame: &[f32], mode: DownmixMode) -> f32 {
+    match mode {
+        DownmixMode::Average => frame.iter().sum::<f32>() / frame.len() as f32,
+        DownmixMode::Left => frame[0],
+        DownmixMode::Right => frame[frame.len().min(2) - 1],
+        DownmixMode::MaxAbs => {
+            frame
+                .iter()
+                .copied()
+                .fold(0.0, |best, s| if s.abs() > best.abs() { s } else { best })
         }
-        AudioBufferRef::U24(buf) => {
-            // Convert u24 to f32 (normalize to [-1.0, 1.0])
-            let scale = 1.0 / 8388608.0;
-            let offset = -1.0;
-            if num_channels == 1 {
-                mono_samples.extend(
-                    buf.chan(0)
-                        .iter()
-                        .map(|&s| s.inner() as f32 * scale + offset),
-                );
-            } else {
-                for i in 0..duration {
-                    let mut sum = 0.0;
-                    for ch in 0..num_channels {
-                        sum += buf.chan(ch)[i].inner() as f32;
-                    }
-                    mono_samples.push((sum / num_channels as f32) * scale + offset);
-                }
-            }
+    }
+}
+
+/// Fraction of samples at or beyond the clipping level
+pub fn clip_fraction(samples: &[f32]) -> f64 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+    let clipped = samples
+        .iter()
+        .filter(|sample| sample.abs() >= CLIP_LEVEL)
+        .count();
+    clipped as f64 / samples.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use symphonia::core::audio::{Channels, SignalSpec};
+
+    fn truncated_wav(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "flowalyzer_decoder_{}_{}.wav",
+            std::process::id(),
+            name
+        ));
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for i in 0..8_000 {
+            writer.write_sample((i % 100) as i16).unwrap();
         }
-        AudioBufferRef::U32(buf) => {
-            // Convert u32 to f32 (normalize to [-1.0, 1.0])
-            let scale = 1.0 / 2147483648.0;
-            let offset = -1.0;
-            if num_channels == 1 {
-                mono_samples.extend(buf.chan(0).iter().map(|&s| s as f32 * scale + offset));
-            } else {
-                for i in 0..duration {
-                    let mut sum = 0.0;
-                    for ch in 0..num_channels {
-                        sum += buf.chan(ch)[i] as f32;
-                    }
-                    mono_samples.push((sum / num_channels as f32) * scale + offset);
-                }
-            }
+        writer.finalize().unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+        path
+    }
+
+    #[test]
+    fn truncated_stream_errors_only_in_strict_mode() {
+        let path = truncated_wav("truncated");
+        let strict = DecodeSettings {
+            strict: true,
+            ..DecodeSettings::default()
+        };
+        let strict_result = decode_audio(&path, &strict);
+        let lenient = decode_audio(&path, &DecodeSettings::default());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(strict_result.is_err());
+        let audio = lenient.unwrap();
+        assert!(!audio.samples.is_empty());
+        assert!(audio.samples.len() < 8_000);
+    }
+
+    #[test]
+    fn duration_cap_stops_decode_early() {
+        let path = std::env::temp_dir().join(format!(
+            "flowalyzer_decoder_{}_long.wav",
+            std::process::id()
+        ));
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8_000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..8_000 * 30 {
+            writer.write_sample(1_000i16).unwrap();
         }
+        writer.finalize().unwrap();
+        let capped = DecodeSettings {
+            max_duration: Some(1.0),
+            ..DecodeSettings::default()
+        };
+
+        let result = decode_audio(&path, &capped);
+        let uncapped = decode_audio(&path, &DecodeSettings::default());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.unwrap_err().to_string().contains("maximum duration"));
+        assert_eq!(uncapped.unwrap().samples.len(), 8_000 * 30);
+        assert!(check_duration_cap(8_000, 8_000, Some(1.0)).is_ok());
+        assert!(check_duration_cap(8_001, 8_000, Some(1.0)).is_err());
+    }
+
+    #[test]
+    fn clip_fraction_of_clean_signal_is_zero() {
+        let samples = vec![0.5; 1_000];
+        assert_eq!(clip_fraction(&samples), 0.0);
+    }
+
+    #[test]
+    fn clip_fraction_counts_full_scale_runs() {
+        let mut samples = vec![0.2; 1_000];
+        samples[100..150].fill(1.0);
+        samples[600..650].fill(-1.0);
+        let fraction = clip_fraction(&samples);
+        assert!((fraction - 0.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn clip_fraction_handles_empty_input() {
+        assert_eq!(clip_fraction(&[]), 0.0);
+    }
+
+    fn anti_phase_buffer() -> AudioBuffer<f32> {
+        let spec = SignalSpec::new(44100, Channels::FRONT_LEFT | Channels::FRONT_RIGHT);
+        let mut buf = AudioBuffer::<f32>::new(4, spec);
+        buf.render_reserved(Some(4));
+        buf.chan_mut(0).copy_from_slice(&[0.5, -0.25, 0.8, 0.1]);
+        buf.chan_mut(1).copy_from_slice(&[-0.5, 0.25, -0.8, -0.1]);
+        buf
+    }
+
+    #[test]
+    fn average_downmix_cancels_anti_phase_channels() {
+        let mono = downmix(&anti_phase_buffer(), DownmixMode::Average, |s| s);
+        assert!(mono.iter().all(|s| s.abs() < 1e-6));
+    }
+
+    #[test]
+    fn max_abs_downmix_preserves_anti_phase_signal() {
+        let mono = downmix(&anti_phase_buffer(), DownmixMode::MaxAbs, |s| s);
+        let peaks: Vec<f32> = mono.iter().map(|s| s.abs()).collect();
+        assert_eq!(peaks, vec![0.5, 0.25, 0.8, 0.1]);
     }
 
-    mono_samples
+    #[test]
+    fn left_and_right_downmix_select_channels() {
+        let buf = anti_phase_buffer();
+        assert_eq!(downmix(&buf, DownmixMode::Left, |s| s)[0], 0.5);
+        assert_eq!(downmix(&buf, DownmixMode::Right, |s| s)[0], -0.5);
+    }
 }
@@ -1,15 +1,54 @@
-use crate::types::AudioData;
+use crate::audio::tags::{self, AudioTags};
+use crate::types::{AudioData, StereoChunk};
 use anyhow::{Context, Result};
-use std::path::Path;
+use serde::Serialize;
+use std::borrow::Cow;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Channel layout written to output files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, clap::ValueEnum)]
+pub enum ChannelsOut {
+    /// Single channel
+    Mono,
+    /// Two channels; mono audio is duplicated to both sides
+    Stereo,
+    /// Keep the pipeline's channel layout (mono)
+    #[default]
+    Same,
+}
+
+impl ChannelsOut {
+    /// Number of channels written
+    pub fn count(self) -> u16 {
+        match self {
+            ChannelsOut::Stereo => 2,
+            ChannelsOut::Mono | ChannelsOut::Same => 1,
+        }
+    }
+}
 
 /// Encode AudioData to WAV format and write to file
-pub fn encode_audio<P: AsRef<Path>>(audio: &AudioData, path: P) -> Result<()> {
-    let path = path.as_ref();
+pub fn encode_audio<P: AsRef<Path>>(
+    audio: &AudioData,
+    path: P,
+    channels_out: ChannelsOut,
+) -> Result<()> {
+    let channels = channels_out.count();
+    let samples: Cow<[f32]> = if channels == 2 {
+        Cow::Owned(duplicate_to_stereo(&audio.samples))
+    } else {
+        Cow::Borrowed(&audio.samples)
+    };
+    write_pcm16(path.as_ref(), audio.sample_rate, channels, &samples)
+}
 
+/// Write interleaved `samples` as 16-bit PCM with `channels` channels
+fn write_pcm16(path: &Path, sample_rate: u32, channels: u16, samples: &[f32]) -> Result<()> {
     // Create WAV writer specification
     let spec = hound::WavSpec {
-        channels: 1,
-        sample_rate: audio.sample_rate,
+        channels,
+        sample_rate,
         bits_per_sample: 16,
         sample_format: hound::SampleFormat::Int,
     };
@@ -19,7 +58,7 @@ pub fn encode_audio<P: AsRef<Path>>(audio: &AudioData, path: P) -> Result<()> {
         .with_context(|| format!("Failed to create WAV file: {}", path.display()))?;
 
     // Write samples as i16
-    for &sample in &audio.samples {
+    for &sample in samples {
         // Clamp to [-1.0, 1.0] and scale to i16 range
         let clamped = sample.clamp(-1.0, 1.0);
         let i16_sample = (clamped * 32767.0) as i16;
@@ -34,3 +73,157 @@ pub fn encode_audio<P: AsRef<Path>>(audio: &AudioData, path: P) -> Result<()> {
 
     Ok(())
 }
+
+/// Encode to a temporary file beside `path` and rename it into place on success,
+/// so a failed or interrupted write never leaves a truncated file at `path`.
+/// `tags`, when given, are embedded as a RIFF INFO chunk before the rename.
+pub fn encode_audio_atomic(
+    audio: &AudioData,
+    path: &Path,
+    channels_out: ChannelsOut,
+    tags: Option<&AudioTags>,
+) -> Result<()> {
+    write_atomic(path, |tmp| {
+        encode_audio(audio, tmp, channels_out)?;
+        write_tags(tmp, tags)
+    })
+}
+
+/// Encode a stereo chunk as a two-channel WAV, atomically and tagged like
+/// `encode_audio_atomic`
+pub fn encode_stereo_atomic(
+    chunk: &StereoChunk,
+    path: &Path,
+    tags: Option<&AudioTags>,
+) -> Result<()> {
+    let samples: Vec<f32> = chunk
+        .left
+        .iter()
+        .zip(&chunk.right)
+        .flat_map(|(&left, &right)| [left, right])
+        .collect();
+    write_atomic(path, |tmp| {
+        write_pcm16(tmp, chunk.sample_rate, 2, &samples)?;
+        write_tags(tmp, tags)
+    })
+}
+
+fn write_tags(path: &Path, tags: Option<&AudioTags>) -> Result<()> {
+    match tags {
+        Some(tags) => tags::write_wav_tags(path, tags),
+        None => Ok(()),
+    }
+}
+
+fn write_atomic(path: &Path, write: impl FnOnce(&Path) -> Result<()>) -> Result<()> {
+    let tmp = partial_path(path);
+    if let Err(err) = write(&tmp) {
+        let _ = fs::remove_file(&tmp);
+        return Err(err);
+    }
+    fs::rename(&tmp, path).with_context(|| format!("Failed to move {:?} into place", tmp))
+}
+
+fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".partial");
+    path.with_file_name(name)
+}
+
+/// Interleave a mono signal into identical left/right channels
+pub fn duplicate_to_stereo(samples: &[f32]) -> Vec<f32> {
+    samples.iter().flat_map(|&s| [s, s]).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_wav(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "flowalyzer_encoder_{}_{}.wav",
+            std::process::id(),
+            name
+        ))
+    }
+
+    fn mono_audio() -> AudioData {
+        AudioData {
+            samples: vec![0.0, 0.25, -0.5, 0.75, 1.0],
+            sample_rate: 8_000,
+        }
+    }
+
+    #[test]
+    fn duplicate_to_stereo_interleaves_pairs() {
+        let stereo = duplicate_to_stereo(&[0.1, -0.2]);
+        assert_eq!(stereo, vec![0.1, 0.1, -0.2, -0.2]);
+    }
+
+    #[test]
+    fn stereo_output_keeps_frame_count() {
+        let path = temp_wav("stereo");
+        encode_audio(&mono_audio(), &path, ChannelsOut::Stereo).unwrap();
+
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(reader.duration(), 5);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn failed_atomic_write_leaves_no_partial_file() {
+        let dir = std::env::temp_dir().join(format!("flowalyzer_atomic_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("processed.wav");
+
+        let result = write_atomic(&path, |tmp| {
+            std::fs::write(tmp, b"RIFF")?;
+            anyhow::bail!("disk full")
+        });
+        let leftovers = std::fs::read_dir(&dir).unwrap().count();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(result.is_err());
+        assert_eq!(leftovers, 0);
+    }
+
+    #[test]
+    fn atomic_encode_replaces_target() {
+        let path = temp_wav("atomic");
+        encode_audio_atomic(&mono_audio(), &path, ChannelsOut::Same, None).unwrap();
+
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.duration(), 5);
+        assert!(!partial_path(&path).exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn stereo_chunk_keeps_its_channels_apart() {
+        let path = temp_wav("panned");
+        let chunk = StereoChunk {
+            left: vec![0.5, 0.0],
+            right: vec![0.0, -0.5],
+            sample_rate: 8_000,
+        };
+        encode_stereo_atomic(&chunk, &path, None).unwrap();
+
+        let mut reader = hound::WavReader::open(&path).unwrap();
+        let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+        assert_eq!(reader.spec().channels, 2);
+        assert_eq!(samples, vec![16383, 0, 0, -16383]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn same_output_stays_mono() {
+        let path = temp_wav("same");
+        encode_audio(&mono_audio(), &path, ChannelsOut::Same).unwrap();
+
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.spec().channels, 1);
+        assert_eq!(reader.duration(), 5);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
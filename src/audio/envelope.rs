@@ -0,0 +1,117 @@
+//! Amplitude envelopes - downsampled peaks for drawing waveform thumbnails
+
+use crate::types::AudioData;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::Path;
+
+/// Per-bucket minimum and maximum sample values
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Envelope {
+    pub min: Vec<f32>,
+    pub max: Vec<f32>,
+}
+
+/// Envelope as written to disk, with the peaks alongside for single-sided waveforms
+#[derive(Serialize)]
+struct EnvelopeFile {
+    #[serde(flatten)]
+    envelope: Envelope,
+    peak: Vec<f32>,
+}
+
+/// Minimum and maximum sample of each of `buckets` equal regions of the audio
+pub fn compute_min_max(audio: &AudioData, buckets: usize) -> Envelope {
+    let (min, max) = bucket_ranges(audio.samples.len(), buckets)
+        .map(|(start, end)| min_max(&audio.samples[start..end]))
+        .unzip();
+    Envelope { min, max }
+}
+
+/// Peak absolute amplitude of each of `buckets` equal regions of the audio
+pub fn compute_envelope(audio: &AudioData, buckets: usize) -> Vec<f32> {
+    let envelope = compute_min_max(audio, buckets);
+    envelope
+        .min
+        .iter()
+        .zip(&envelope.max)
+        .map(|(lo, hi)| lo.abs().max(hi.abs()))
+        .collect()
+}
+
+/// Write the min/max envelope of `audio`, plus per-bucket peaks, as JSON
+pub fn write_envelope(audio: &AudioData, buckets: usize, path: &Path) -> Result<()> {
+    let file = EnvelopeFile {
+        envelope: compute_min_max(audio, buckets),
+        peak: compute_envelope(audio, buckets),
+    };
+    let json = serde_json::to_string(&file).context("Failed to serialize envelope")?;
+    std::fs::write(path, json).with_context(|| format!("Failed to write envelope {:?}", path))
+}
+
+/// Sample index ranges for each bucket; buckets past the end of short audio are empty
+fn bucket_ranges(len: usize, buckets: usize) -> impl Iterator<Item = (usize, usize)> {
+    (0..buckets).map(move |idx| (idx * len / buckets, (idx + 1) * len / buckets))
+}
+
+fn min_max(samples: &[f32]) -> (f32, f32) {
+    if samples.is_empty() {
+        return (0.0, 0.0);
+    }
+    samples
+        .iter()
+        .fold((f32::MAX, f32::MIN), |(lo, hi), &s| (lo.min(s), hi.max(s)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio(samples: Vec<f32>) -> AudioData {
+        AudioData {
+            samples,
+            sample_rate: 8_000,
+        }
+    }
+
+    #[test]
+    fn envelope_has_requested_length_and_region_peaks() {
+        let mut samples = vec![0.0; 400];
+        samples[10] = 0.3;
+        samples[150] = -0.8;
+        samples[250] = 0.5;
+        samples[399] = -0.1;
+        let envelope = compute_envelope(&audio(samples), 4);
+        assert_eq!(envelope, vec![0.3, 0.8, 0.5, 0.1]);
+    }
+
+    #[test]
+    fn envelope_pads_short_audio_with_silent_buckets() {
+        let envelope = compute_envelope(&audio(vec![0.5, -0.25]), 4);
+        assert_eq!(envelope.len(), 4);
+        assert_eq!(envelope.iter().filter(|&&peak| peak > 0.0).count(), 2);
+    }
+
+    #[test]
+    fn min_max_tracks_both_extremes() {
+        let envelope = compute_min_max(&audio(vec![0.2, -0.4, 0.9, -0.1]), 2);
+        assert_eq!(envelope.min, vec![-0.4, -0.1]);
+        assert_eq!(envelope.max, vec![0.2, 0.9]);
+    }
+
+    #[test]
+    fn written_envelope_includes_peaks() {
+        let path = std::env::temp_dir().join(format!(
+            "flowalyzer_envelope_{}_peaks.json",
+            std::process::id()
+        ));
+        write_envelope(&audio(vec![0.2, -0.4, 0.9, -0.1]), 2, &path).unwrap();
+        let json: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let field =
+            |name: &str| -> Vec<f32> { serde_json::from_value(json[name].clone()).unwrap() };
+        assert_eq!(field("peak"), vec![0.4, 0.9]);
+        assert_eq!(field("min"), vec![-0.4, -0.1]);
+    }
+}
@@ -0,0 +1,144 @@
+//! Biquad filters - restrict audio to the speech band before energy analysis,
+//! and band-limit it before downsampling
+//!
+//! Coefficients follow the RBJ audio EQ cookbook with Butterworth Q, so each
+//! stage rolls off at 12 dB/octave outside its corner frequency.
+
+use crate::types::AudioData;
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
+
+/// Lower edge of the telephone speech band in Hz
+pub const SPEECH_BAND_LOW_HZ: f64 = 300.0;
+/// Upper edge of the telephone speech band in Hz
+pub const SPEECH_BAND_HIGH_HZ: f64 = 3400.0;
+
+/// Low-pass sections run in each direction by `low_pass_zero_phase`
+const ZERO_PHASE_STAGES: usize = 2;
+
+/// Second-order IIR filter section (direct form I)
+#[derive(Debug, Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+}
+
+impl Biquad {
+    fn high_pass(cutoff_hz: f64, sample_rate: u32) -> Self {
+        let (cos, alpha) = Self::angle(cutoff_hz, sample_rate);
+        let a0 = 1.0 + alpha;
+        let b = [(1.0 + cos) / 2.0, -(1.0 + cos), (1.0 + cos) / 2.0];
+        Self::normalized(b, [-2.0 * cos, 1.0 - alpha], a0)
+    }
+
+    fn low_pass(cutoff_hz: f64, sample_rate: u32) -> Self {
+        let (cos, alpha) = Self::angle(cutoff_hz, sample_rate);
+        let a0 = 1.0 + alpha;
+        let b = [(1.0 - cos) / 2.0, 1.0 - cos, (1.0 - cos) / 2.0];
+        Self::normalized(b, [-2.0 * cos, 1.0 - alpha], a0)
+    }
+
+    fn angle(cutoff_hz: f64, sample_rate: u32) -> (f64, f64) {
+        let omega = 2.0 * PI * cutoff_hz / sample_rate as f64;
+        (omega.cos(), omega.sin() / (2.0 * FRAC_1_SQRT_2))
+    }
+
+    fn normalized(b: [f64; 3], a: [f64; 2], a0: f64) -> Self {
+        Self {
+            b: b.map(|coef| coef / a0),
+            a: a.map(|coef| coef / a0),
+        }
+    }
+
+    fn apply(&self, samples: &[f32]) -> Vec<f32> {
+        self.run(samples, 0.0)
+    }
+
+    /// Like `apply`, but starting as if the first sample had always been present.
+    /// Only valid for unity-DC-gain (low-pass) sections; avoids a start-up transient.
+    fn apply_settled(&self, samples: &[f32]) -> Vec<f32> {
+        self.run(samples, samples.first().copied().unwrap_or(0.0) as f64)
+    }
+
+    /// Filter with input and output history both starting at `settled`
+    fn run(&self, samples: &[f32], settled: f64) -> Vec<f32> {
+        let (mut x1, mut x2, mut y1, mut y2) = (settled, settled, settled, settled);
+        samples
+            .iter()
+            .map(|&sample| {
+                let x0 = sample as f64;
+                let y0 = self.b[0] * x0 + self.b[1] * x1 + self.b[2] * x2
+                    - self.a[0] * y1
+                    - self.a[1] * y2;
+                (x2, x1, y2, y1) = (x1, x0, y1, y0);
+                y0 as f32
+            })
+            .collect()
+    }
+}
+
+/// Low-pass `samples` below `cutoff_hz`, filtering forwards then backwards so the
+/// result has no phase shift and stays aligned with the input
+pub fn low_pass_zero_phase(samples: &[f32], cutoff_hz: f64, sample_rate: u32) -> Vec<f32> {
+    let section = Biquad::low_pass(cutoff_hz, sample_rate);
+    let mut filtered = samples.to_vec();
+    for _ in 0..ZERO_PHASE_STAGES {
+        filtered = section.apply_settled(&filtered);
+    }
+    filtered.reverse();
+    for _ in 0..ZERO_PHASE_STAGES {
+        filtered = section.apply_settled(&filtered);
+    }
+    filtered.reverse();
+    filtered
+}
+
+/// Band-pass audio to 300–3400 Hz so rumble and hiss don't count as speech energy
+pub fn speech_band_pass(audio: &AudioData) -> AudioData {
+    let nyquist = audio.sample_rate as f64 / 2.0;
+    let high_pass = Biquad::high_pass(SPEECH_BAND_LOW_HZ, audio.sample_rate);
+    let mut samples = high_pass.apply(&audio.samples);
+    if SPEECH_BAND_HIGH_HZ < nyquist {
+        samples = Biquad::low_pass(SPEECH_BAND_HIGH_HZ, audio.sample_rate).apply(&samples);
+    }
+    AudioData {
+        samples,
+        sample_rate: audio.sample_rate,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tone(freq: f32, sample_rate: u32, len: usize) -> AudioData {
+        AudioData {
+            samples: (0..len)
+                .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / sample_rate as f32).sin())
+                .collect(),
+            sample_rate,
+        }
+    }
+
+    fn tail_peak(audio: &AudioData) -> f32 {
+        let tail = &audio.samples[audio.samples.len() / 2..];
+        tail.iter().fold(0.0f32, |peak, s| peak.max(s.abs()))
+    }
+
+    #[test]
+    fn passes_speech_and_rejects_rumble_and_hiss() {
+        assert!(tail_peak(&speech_band_pass(&tone(1_000.0, 16_000, 8_000))) > 0.8);
+        assert!(tail_peak(&speech_band_pass(&tone(50.0, 16_000, 8_000))) < 0.05);
+        assert!(tail_peak(&speech_band_pass(&tone(7_000.0, 16_000, 8_000))) < 0.2);
+    }
+
+    #[test]
+    fn zero_phase_low_pass_keeps_timing_and_holds_dc() {
+        let low = tone(100.0, 16_000, 8_000);
+        let filtered = low_pass_zero_phase(&low.samples, 4_000.0, 16_000);
+        for (a, b) in low.samples.iter().zip(&filtered).skip(100).take(7_800) {
+            assert!((a - b).abs() < 0.01);
+        }
+        let dc = low_pass_zero_phase(&[0.5; 64], 4_000.0, 16_000);
+        assert!(dc.iter().all(|s| (s - 0.5).abs() < 1e-5));
+    }
+}
@@ -1,5 +1,9 @@
 pub mod assembler;
 pub mod decoder;
 pub mod encoder;
+pub mod envelope;
+pub mod filter;
 pub mod pause_detector;
+pub mod resample;
 pub mod slicer;
+pub mod tags;
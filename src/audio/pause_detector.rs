@@ -1,5 +1,8 @@
 use crate::types::AudioData;
 
+/// Mean window amplitude at or below which audio counts as silence (about -28 dBFS)
+pub const DEFAULT_SILENCE_THRESHOLD: f32 = 0.04;
+
 /// Detect pause timestamps (in seconds) based on windowed energy analysis.
 ///
 /// # Parameters
@@ -7,7 +10,6 @@ use crate::types::AudioData;
 /// * `window_ms` - window size in milliseconds (e.g. 20.0)
 /// * `min_silence_ms` - minimum consecutive silence needed to declare a pause (e.g. 80.0)
 /// * `threshold` - amplitude threshold (linear 0.0-1.0 range) for silence detection
-#[cfg_attr(not(test), allow(dead_code))]
 pub fn detect_pauses(
     audio: &AudioData,
     window_ms: f64,
@@ -59,7 +61,11 @@ pub fn detect_pauses(
     pauses
 }
 
-#[cfg_attr(not(test), allow(dead_code))]
+/// Convert a level in dBFS (e.g. -40.0) to the linear amplitude `detect_pauses` expects
+pub fn dbfs_to_amplitude(db: f64) -> f32 {
+    10f64.powf(db / 20.0) as f32
+}
+
 fn window_energy(window: &[f32]) -> f32 {
     if window.is_empty() {
         return 0.0;
@@ -100,6 +106,44 @@ mod tests {
         assert!((pause_time - 0.6).abs() < 0.05); // roughly middle of quiet region
     }
 
+    #[test]
+    fn rumble_is_silent_only_after_speech_band_filter() {
+        // 0.5s 1 kHz speech stand-in, 0.3s of 50 Hz rumble, 0.5s speech again
+        let sample_rate = 16_000;
+        let wave = |freq: f32, amp: f32, len: usize| {
+            (0..len).map(move |i| {
+                amp * (2.0 * std::f32::consts::PI * freq * i as f32 / sample_rate as f32).sin()
+            })
+        };
+        let samples: Vec<f32> = wave(1_000.0, 0.5, 8_000)
+            .chain(wave(50.0, 0.3, 4_800))
+            .chain(wave(1_000.0, 0.5, 8_000))
+            .collect();
+        let audio = make_audio(samples, sample_rate);
+
+        assert!(detect_pauses(&audio, 20.0, 80.0, 0.1).is_empty());
+
+        let filtered = crate::audio::filter::speech_band_pass(&audio);
+        let pauses = detect_pauses(&filtered, 20.0, 80.0, 0.1);
+        assert_eq!(pauses.len(), 1);
+        assert!((pauses[0] - 0.65).abs() < 0.05);
+    }
+
+    #[test]
+    fn dbfs_threshold_matches_linear_equivalent() {
+        let threshold = dbfs_to_amplitude(-40.0);
+        assert!((threshold - 0.01).abs() < 1e-6);
+        assert!((dbfs_to_amplitude(0.0) - 1.0).abs() < 1e-6);
+
+        let mut samples = vec![0.8; 5_000];
+        samples.extend(vec![0.005; 2_000]);
+        samples.extend(vec![0.8; 5_000]);
+        let audio = make_audio(samples, 10_000);
+        let from_db = detect_pauses(&audio, 20.0, 80.0, threshold);
+        assert_eq!(from_db, detect_pauses(&audio, 20.0, 80.0, 0.01));
+        assert_eq!(from_db.len(), 1);
+    }
+
     #[test]
     fn short_silence_ignored() {
         // 0.5s loud, 0.04s quiet, 0.5s loud
@@ -0,0 +1,121 @@
+use super::filter::low_pass_zero_phase;
+use crate::types::AudioData;
+
+/// Anti-alias cutoff as a fraction of the target Nyquist frequency
+const ANTI_ALIAS_FRACTION: f64 = 0.9;
+
+/// Pure function to convert audio to a new sample rate using linear interpolation
+pub fn resample(audio: &AudioData, target_rate: u32) -> AudioData {
+    AudioData {
+        samples: linear_resample(&audio.samples, audio.sample_rate, target_rate),
+        sample_rate: target_rate,
+    }
+}
+
+/// Linearly interpolate `samples` from `from_rate` to `to_rate`
+///
+/// When downsampling, content above the new Nyquist frequency is low-passed away
+/// first so it can't fold back into the audible band.
+pub fn linear_resample(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let ratio = from_rate as f64 / to_rate as f64;
+    let output_len = (samples.len() as f64 / ratio).round() as usize;
+    if to_rate < from_rate {
+        let cutoff = ANTI_ALIAS_FRACTION * to_rate as f64 / 2.0;
+        let band_limited = low_pass_zero_phase(samples, cutoff, from_rate);
+        return resample_exact(&band_limited, from_rate, to_rate, output_len);
+    }
+    resample_exact(samples, from_rate, to_rate, output_len)
+}
+
+/// Interpolate `samples` from `from_rate` to `to_rate`, producing exactly `target_len` samples
+///
+/// Positions are computed per output index rather than accumulated, so long
+/// inputs never drift; the tail is padded with the last sample if needed.
+pub fn resample_exact(
+    samples: &[f32],
+    from_rate: u32,
+    to_rate: u32,
+    target_len: usize,
+) -> Vec<f32> {
+    resample_by_ratio(samples, from_rate as f64 / to_rate as f64, target_len)
+}
+
+/// Interpolate `samples`, advancing `ratio` input samples per output sample, into exactly `target_len` samples
+pub fn resample_by_ratio(samples: &[f32], ratio: f64, target_len: usize) -> Vec<f32> {
+    if samples.is_empty() {
+        return vec![0.0; target_len];
+    }
+    (0..target_len)
+        .map(|idx| interpolate(samples, idx as f64 * ratio))
+        .collect()
+}
+
+fn interpolate(samples: &[f32], position: f64) -> f32 {
+    let last = samples.len() - 1;
+    let base = (position.floor() as usize).min(last);
+    let next = (base + 1).min(last);
+    let frac = (position - base as f64) as f32;
+    samples[base] * (1.0 - frac) + samples[next] * frac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_rate_is_identity() {
+        let samples = vec![0.1, 0.2, 0.3];
+        assert_eq!(linear_resample(&samples, 16_000, 16_000), samples);
+    }
+
+    #[test]
+    fn downsample_44100_to_16000_scales_length() {
+        let audio = AudioData {
+            samples: vec![0.0; 44_100],
+            sample_rate: 44_100,
+        };
+        let resampled = resample(&audio, 16_000);
+        assert_eq!(resampled.sample_rate, 16_000);
+        assert_eq!(resampled.samples.len(), 16_000);
+    }
+
+    #[test]
+    fn resample_exact_hits_requested_length() {
+        let samples: Vec<f32> = (0..44_100).map(|i| (i as f32 / 100.0).sin()).collect();
+        let resampled = resample_exact(&samples, 44_100, 16_000, 16_000);
+        assert_eq!(resampled.len(), 16_000);
+        assert!((resampled[8_000] - samples[22_050]).abs() < 1e-3);
+    }
+
+    #[test]
+    fn resample_exact_pads_when_target_exceeds_source() {
+        let resampled = resample_exact(&[0.25, 0.5], 2, 2, 4);
+        assert_eq!(resampled, vec![0.25, 0.5, 0.5, 0.5]);
+        assert_eq!(resample_exact(&[], 2, 1, 3), vec![0.0; 3]);
+    }
+
+    #[test]
+    fn downsampling_attenuates_tones_above_the_new_nyquist() {
+        let tone = |freq: f32| -> Vec<f32> {
+            (0..44_100)
+                .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / 44_100.0).sin())
+                .collect()
+        };
+        let peak = |samples: &[f32]| samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+
+        // 12 kHz would alias to 4 kHz at 16 kHz without the low-pass
+        let aliased = linear_resample(&tone(12_000.0), 44_100, 16_000);
+        let speech = linear_resample(&tone(1_000.0), 44_100, 16_000);
+        assert!(peak(&aliased[1_000..15_000]) < 0.05);
+        assert!(peak(&speech[1_000..15_000]) > 0.9);
+    }
+
+    #[test]
+    fn upsample_interpolates_between_samples() {
+        let resampled = linear_resample(&[0.0, 1.0], 1, 2);
+        assert_eq!(resampled, vec![0.0, 0.5, 1.0, 1.0]);
+    }
+}
@@ -1,30 +1,32 @@
 use crate::types::{AudioChunk, AudioData, ChunkBoundary};
 
 /// Pure function to slice audio data into chunks based on time boundaries
-pub fn slice_audio(audio: &AudioData, boundaries: &[ChunkBoundary]) -> Vec<AudioChunk> {
-    let mut chunks = Vec::with_capacity(boundaries.len());
-
-    for boundary in boundaries {
-        // Convert time boundaries to sample indices
-        let start_sample = (boundary.start_time * audio.sample_rate as f64) as usize;
-        let end_sample = (boundary.end_time * audio.sample_rate as f64) as usize;
-
-        // Clamp to valid range
-        let start_sample = start_sample.min(audio.samples.len());
-        let end_sample = end_sample.min(audio.samples.len());
-
-        // Extract samples for this chunk
-        let samples = audio.samples[start_sample..end_sample].to_vec();
-
-        chunks.push(AudioChunk {
-            samples,
-            sample_rate: audio.sample_rate,
-            start_time: boundary.start_time,
-            end_time: boundary.end_time,
-        });
-    }
+///
+/// Slices lazily so only one chunk's samples are materialized at a time
+pub fn slice_audio_iter<'a>(
+    audio: &'a AudioData,
+    boundaries: &'a [ChunkBoundary],
+) -> impl Iterator<Item = AudioChunk> + 'a {
+    boundaries
+        .iter()
+        .map(move |boundary| slice_boundary(audio, boundary))
+}
+
+fn slice_boundary(audio: &AudioData, boundary: &ChunkBoundary) -> AudioChunk {
+    // Convert time boundaries to sample indices
+    let start_sample = (boundary.start_time * audio.sample_rate as f64) as usize;
+    let end_sample = (boundary.end_time * audio.sample_rate as f64) as usize;
+
+    // Clamp to valid range
+    let start_sample = start_sample.min(audio.samples.len());
+    let end_sample = end_sample.min(audio.samples.len());
 
-    chunks
+    AudioChunk {
+        samples: audio.samples[start_sample..end_sample].to_vec(),
+        sample_rate: audio.sample_rate,
+        start_time: boundary.start_time,
+        end_time: boundary.end_time,
+    }
 }
 
 #[cfg(test)]
@@ -52,7 +54,7 @@ mod tests {
             },
         ];
 
-        let chunks = slice_audio(&audio, &boundaries);
+        let chunks: Vec<AudioChunk> = slice_audio_iter(&audio, &boundaries).collect();
 
         assert_eq!(chunks.len(), 2);
         assert_eq!(chunks[0].samples.len(), 22050); // 0.5 * 44100
@@ -73,10 +75,36 @@ mod tests {
             source_segment_ids: vec![0],
         }];
 
-        let chunks = slice_audio(&audio, &boundaries);
+        let chunks: Vec<AudioChunk> = slice_audio_iter(&audio, &boundaries).collect();
 
         assert_eq!(chunks.len(), 1);
         // Should clamp to available samples
         assert_eq!(chunks[0].samples.len(), 500); // 0.5 seconds worth
     }
+
+    #[test]
+    fn test_overlapping_boundaries_slice_independently() {
+        let audio = AudioData {
+            samples: (0..3000).map(|i| (i as f32 * 0.01).sin()).collect(),
+            sample_rate: 1000,
+        };
+
+        let boundaries: Vec<ChunkBoundary> = (0..3)
+            .map(|i| ChunkBoundary {
+                start_time: i as f64 * 0.9,
+                end_time: i as f64 * 0.9 + 1.1,
+                source_segment_ids: vec![i],
+            })
+            .collect();
+
+        let chunks: Vec<AudioChunk> = slice_audio_iter(&audio, &boundaries).collect();
+
+        assert_eq!(chunks.len(), 3);
+        for (i, chunk) in chunks.iter().enumerate() {
+            let start = i * 900;
+            assert_eq!(chunk.samples, audio.samples[start..start + 1100]);
+            assert_eq!(chunk.start_time, boundaries[i].start_time);
+            assert_eq!(chunk.end_time, boundaries[i].end_time);
+        }
+    }
 }
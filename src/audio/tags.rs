@@ -0,0 +1,192 @@
+//! Descriptive tags (title, artist, album) read from a source file and written
+//! to WAV outputs as a RIFF `LIST/INFO` chunk.
+
+use anyhow::{ensure, Context, Result};
+use std::fs;
+use std::path::Path;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::{MetadataOptions, MetadataRevision, StandardTagKey, Value};
+use symphonia::core::probe::Hint;
+
+/// Tags carried from a source file onto the files written from it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AudioTags {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub track: Option<String>,
+}
+
+impl AudioTags {
+    /// Tags for chunk `number` (1-based): the title gains a ` (n)` suffix, falling back
+    /// to `fallback_title` when the source has none, and the track number is `n`
+    pub fn for_chunk(&self, fallback_title: &str, number: usize) -> AudioTags {
+        let title = self.title.as_deref().unwrap_or(fallback_title);
+        AudioTags {
+            title: Some(format!("{} ({})", title, number)),
+            artist: self.artist.clone(),
+            album: self.album.clone(),
+            track: Some(number.to_string()),
+        }
+    }
+
+    fn info_entries(&self) -> impl Iterator<Item = (&'static [u8; 4], &str)> {
+        [
+            (b"INAM", &self.title),
+            (b"IART", &self.artist),
+            (b"IPRD", &self.album),
+            (b"IPRT", &self.track),
+        ]
+        .into_iter()
+        .filter_map(|(id, value)| value.as_deref().map(|v| (id, v)))
+    }
+}
+
+/// Read title/artist/album/track tags from any format symphonia can probe
+pub fn read_tags(path: &Path) -> Result<AudioTags> {
+    let file = fs::File::open(path)
+        .with_context(|| format!("Failed to open audio file: {}", path.display()))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|e| e.to_str()) {
+        hint.with_extension(extension);
+    }
+    let mut probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .with_context(|| format!("Failed to probe tags in {}", path.display()))?;
+
+    let mut tags = AudioTags::default();
+    // Container-level tags (e.g. ID3v2 ahead of an MP3 stream) first, then the format's own
+    if let Some(revision) = probed.metadata.get().as_ref().and_then(|m| m.current()) {
+        merge_revision(&mut tags, revision);
+    }
+    if let Some(revision) = probed.format.metadata().current() {
+        merge_revision(&mut tags, revision);
+    }
+    Ok(tags)
+}
+
+fn merge_revision(tags: &mut AudioTags, revision: &MetadataRevision) {
+    for tag in revision.tags() {
+        let slot = match tag.std_key {
+            Some(StandardTagKey::TrackTitle) => &mut tags.title,
+            Some(StandardTagKey::Artist) => &mut tags.artist,
+            Some(StandardTagKey::Album) => &mut tags.album,
+            Some(StandardTagKey::TrackNumber) => &mut tags.track,
+            _ => continue,
+        };
+        let text = match &tag.value {
+            Value::String(s) => s.trim_end_matches('\0').trim().to_string(),
+            other => other.to_string(),
+        };
+        if !text.is_empty() {
+            *slot = Some(text);
+        }
+    }
+}
+
+/// Insert a `LIST/INFO` chunk holding `tags` into the WAV at `path`.
+///
+/// The chunk goes before `data`, since readers such as symphonia only pick up
+/// INFO chunks that precede the sample data.
+pub fn write_wav_tags(path: &Path, tags: &AudioTags) -> Result<()> {
+    let mut bytes = fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+    ensure!(
+        bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WAVE",
+        "{:?} is not a RIFF/WAVE file",
+        path
+    );
+    let data_offset =
+        find_chunk(&bytes, b"data").with_context(|| format!("No data chunk in {:?}", path))?;
+
+    let info = info_chunk(tags);
+    bytes.splice(data_offset..data_offset, info);
+    let riff_size = u32::try_from(bytes.len() - 8).context("Tagged WAV exceeds 4 GiB")?;
+    bytes[4..8].copy_from_slice(&riff_size.to_le_bytes());
+    fs::write(path, bytes).with_context(|| format!("Failed to write tags to {:?}", path))
+}
+
+/// Byte offset of the first top-level chunk with id `id`
+fn find_chunk(bytes: &[u8], id: &[u8; 4]) -> Option<usize> {
+    let mut offset = 12;
+    while offset + 8 <= bytes.len() {
+        if &bytes[offset..offset + 4] == id {
+            return Some(offset);
+        }
+        let size = u32::from_le_bytes(bytes[offset + 4..offset + 8].try_into().ok()?) as usize;
+        offset += 8 + size + size % 2;
+    }
+    None
+}
+
+fn info_chunk(tags: &AudioTags) -> Vec<u8> {
+    let mut body = b"INFO".to_vec();
+    for (id, value) in tags.info_entries() {
+        // NUL-terminated, padded with a second NUL to keep the sub-chunk word aligned
+        let mut text = value.as_bytes().to_vec();
+        text.push(0);
+        if text.len() % 2 == 1 {
+            text.push(0);
+        }
+        body.extend_from_slice(id);
+        body.extend_from_slice(&(text.len() as u32).to_le_bytes());
+        body.extend_from_slice(&text);
+    }
+    let mut chunk = b"LIST".to_vec();
+    chunk.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    chunk.extend_from_slice(&body);
+    chunk
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::encoder::{encode_audio, ChannelsOut};
+    use crate::types::AudioData;
+
+    #[test]
+    fn written_tags_read_back() {
+        let path = std::env::temp_dir().join(format!(
+            "flowalyzer_tags_{}_roundtrip.wav",
+            std::process::id()
+        ));
+        let audio = AudioData {
+            samples: vec![0.0, 0.5, -0.5],
+            sample_rate: 8_000,
+        };
+        encode_audio(&audio, &path, ChannelsOut::Same).unwrap();
+        let tags = AudioTags {
+            title: Some("Lesson".into()),
+            artist: Some("Narrator".into()),
+            album: Some("Course One".into()),
+            track: None,
+        };
+        write_wav_tags(&path, &tags).unwrap();
+
+        let read = read_tags(&path).unwrap();
+        let reader = hound::WavReader::open(&path).unwrap();
+        assert_eq!(reader.duration(), 3);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read, tags);
+    }
+
+    #[test]
+    fn chunk_tags_number_the_title() {
+        let source = AudioTags {
+            title: None,
+            artist: Some("Narrator".into()),
+            album: Some("Course One".into()),
+            track: Some("4".into()),
+        };
+        let chunk = source.for_chunk("lesson", 3);
+        assert_eq!(chunk.title.as_deref(), Some("lesson (3)"));
+        assert_eq!(chunk.track.as_deref(), Some("3"));
+        assert_eq!(chunk.album, source.album);
+    }
+}
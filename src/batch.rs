@@ -0,0 +1,73 @@
+//! Batch processing - run a per-file job over many inputs with bounded concurrency
+//!
+//! Each worker thread pulls the next unclaimed input, so at most `max_parallel`
+//! jobs (and Whisper instances) are alive at once.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Run `job` over every item with at most `max_parallel` running concurrently,
+/// returning results in input order
+pub fn run_bounded<T, R, F>(items: &[T], max_parallel: usize, job: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = max_parallel.clamp(1, items.len().max(1));
+    if workers == 1 {
+        return items.iter().map(job).collect();
+    }
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(items.len()));
+    thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                let result = job(item);
+                results.lock().unwrap().push((index, result));
+            });
+        }
+    });
+    let mut results = results.into_inner().unwrap();
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn max_concurrency(limit: usize, files: usize) -> (usize, Vec<usize>) {
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let inputs: Vec<usize> = (0..files).collect();
+        let results = run_bounded(&inputs, limit, |&input| {
+            let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(20));
+            active.fetch_sub(1, Ordering::SeqCst);
+            input * 10
+        });
+        (peak.into_inner(), results)
+    }
+
+    #[test]
+    fn limit_one_runs_files_one_at_a_time() {
+        let (peak, results) = max_concurrency(1, 3);
+        assert_eq!(peak, 1);
+        assert_eq!(results, vec![0, 10, 20]);
+    }
+
+    #[test]
+    fn higher_limit_stays_bounded_and_ordered() {
+        let (peak, results) = max_concurrency(2, 5);
+        assert!(peak <= 2);
+        assert_eq!(results, vec![0, 10, 20, 30, 40]);
+    }
+}
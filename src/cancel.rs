@@ -0,0 +1,48 @@
+//! Cooperative cancellation - a shared flag checked between pipeline stages
+
+use anyhow::Result;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Shared flag an embedder sets to stop a run early
+pub type CancelFlag = Arc<AtomicBool>;
+
+/// Error returned when a run stops because its cancel flag was set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Processing cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// Whether the flag has been set
+pub fn is_cancelled(flag: &AtomicBool) -> bool {
+    flag.load(Ordering::Relaxed)
+}
+
+/// Fail with `Cancelled` once the flag has been set
+pub fn check(flag: &AtomicBool) -> Result<()> {
+    if is_cancelled(flag) {
+        return Err(Cancelled.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_fails_only_after_cancel() {
+        let flag = CancelFlag::default();
+        assert!(check(&flag).is_ok());
+        flag.store(true, Ordering::Relaxed);
+        let err = check(&flag).unwrap_err();
+        assert!(err.is::<Cancelled>());
+    }
+}
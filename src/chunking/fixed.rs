@@ -0,0 +1,43 @@
+use crate::types::ChunkBoundary;
+
+use super::spans::EPS;
+
+/// A trailing chunk shorter than this fraction of `chunk_duration` is merged
+/// into the previous chunk instead of being emitted on its own
+const MIN_FINAL_FRACTION: f64 = 0.5;
+
+/// Pure function emitting back-to-back boundaries of `chunk_duration` seconds
+/// that cover `[0, total_duration)`, ignoring any transcript
+pub(crate) fn fixed_chunk_boundaries(
+    total_duration: f64,
+    chunk_duration: f64,
+) -> Vec<ChunkBoundary> {
+    let mut boundaries = Vec::new();
+    let mut start = 0.0;
+    while start < total_duration - EPS {
+        let end = (start + chunk_duration).min(total_duration);
+        boundaries.push(ChunkBoundary {
+            start_time: start,
+            end_time: end,
+            source_segment_ids: Vec::new(),
+        });
+        start = end;
+    }
+    merge_short_tail(&mut boundaries, chunk_duration);
+    boundaries
+}
+
+fn merge_short_tail(boundaries: &mut Vec<ChunkBoundary>, chunk_duration: f64) {
+    if boundaries.len() < 2 {
+        return;
+    }
+    let last = &boundaries[boundaries.len() - 1];
+    if last.end_time - last.start_time >= chunk_duration * MIN_FINAL_FRACTION - EPS {
+        return;
+    }
+    let tail_end = last.end_time;
+    boundaries.pop();
+    if let Some(previous) = boundaries.last_mut() {
+        previous.end_time = tail_end;
+    }
+}
@@ -1,7 +1,15 @@
 mod accumulator;
+mod fixed;
+mod natural;
+mod overlap;
+mod phrase;
 mod planner;
 mod spans;
 
+pub(crate) use fixed::fixed_chunk_boundaries;
+pub(crate) use natural::natural_pauses;
+pub(crate) use overlap::{apply_context_tail, apply_overlap, close_gaps};
+pub(crate) use phrase::phrase_chunk_boundaries;
 pub(crate) use planner::calculate_chunk_boundaries;
 
 #[cfg(test)]
@@ -0,0 +1,21 @@
+use crate::types::{ChunkBoundary, Transcript};
+
+/// The speaker's pause after each chunk: the gap between the chunk's last transcript
+/// segment and the segment that follows it.
+///
+/// `None` for chunks without transcript segments (e.g. fixed-duration chunks) and for
+/// a chunk ending on the final segment.
+pub(crate) fn natural_pauses(
+    boundaries: &[ChunkBoundary],
+    transcript: &Transcript,
+) -> Vec<Option<f64>> {
+    boundaries
+        .iter()
+        .map(|boundary| {
+            let last = *boundary.source_segment_ids.iter().max()?;
+            let current = transcript.segments.get(last)?;
+            let next = transcript.segments.get(last + 1)?;
+            Some((next.start_time - current.end_time).max(0.0))
+        })
+        .collect()
+}
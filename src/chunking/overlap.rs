@@ -0,0 +1,82 @@
+use crate::types::{ChunkBoundary, Granularity, Transcript};
+
+/// Slack for segments that start fractionally before a boundary's end due to rounding
+const TAIL_EPSILON: f64 = 1e-6;
+
+/// Pure function extending each boundary's start backward by `overlap` seconds
+/// (never before the previous chunk's start), adding any transcript segments
+/// that the extended region now covers
+pub(crate) fn apply_overlap(
+    boundaries: &[ChunkBoundary],
+    overlap: f64,
+    transcript: &Transcript,
+) -> Vec<ChunkBoundary> {
+    let mut overlapped = boundaries.to_vec();
+    for idx in 1..overlapped.len() {
+        let floor = boundaries[idx - 1].start_time;
+        let original_start = boundaries[idx].start_time;
+        let start = (original_start - overlap).max(floor);
+        let boundary = &mut overlapped[idx];
+        boundary.start_time = start;
+        prepend_covered_segments(boundary, transcript, start, original_start);
+    }
+    overlapped
+}
+
+/// Pure function extending each boundary's end forward to cover up to `words`
+/// word segments that start at or after it, so phrases aren't cut off mid-thought;
+/// the tail stops at the first phrase or sentence segment, and the extra segments
+/// are context and are not added to `source_segment_ids`
+pub(crate) fn apply_context_tail(
+    boundaries: &[ChunkBoundary],
+    words: usize,
+    transcript: &Transcript,
+) -> Vec<ChunkBoundary> {
+    let mut extended = boundaries.to_vec();
+    if words == 0 {
+        return extended;
+    }
+    for boundary in &mut extended {
+        let original_end = boundary.end_time;
+        let tail_end = transcript
+            .segments
+            .iter()
+            .filter(|segment| segment.start_time >= original_end - TAIL_EPSILON)
+            .take_while(|segment| segment.granularity == Granularity::Word)
+            .take(words)
+            .map(|segment| segment.end_time)
+            .fold(original_end, f64::max);
+        boundary.end_time = tail_end;
+    }
+    extended
+}
+
+/// Pure function extending each boundary's end to the next boundary's start, so
+/// the pause between chunks belongs to the chunk before it; the last is unchanged
+pub(crate) fn close_gaps(boundaries: &[ChunkBoundary]) -> Vec<ChunkBoundary> {
+    let mut closed = boundaries.to_vec();
+    for idx in 1..closed.len() {
+        let next_start = boundaries[idx].start_time;
+        let boundary = &mut closed[idx - 1];
+        boundary.end_time = boundary.end_time.max(next_start);
+    }
+    closed
+}
+
+fn prepend_covered_segments(
+    boundary: &mut ChunkBoundary,
+    transcript: &Transcript,
+    from: f64,
+    to: f64,
+) {
+    let mut ids: Vec<usize> = transcript
+        .segments
+        .iter()
+        .enumerate()
+        .filter(|(_, segment)| segment.start_time < to && segment.end_time > from)
+        .map(|(idx, _)| idx)
+        .filter(|idx| !boundary.source_segment_ids.contains(idx))
+        .collect();
+    ids.append(&mut boundary.source_segment_ids);
+    boundary.source_segment_ids = ids;
+}
@@ -0,0 +1,31 @@
+use crate::types::{ChunkBoundary, Granularity, Transcript};
+
+/// Pure function grouping transcript segments into one chunk per phrase
+///
+/// Word segments accumulate until a phrase- or sentence-length segment closes
+/// the run, so each chunk ends where a spoken phrase does. Trailing words with
+/// no closing phrase form a final chunk.
+pub(crate) fn phrase_chunk_boundaries(transcript: &Transcript) -> Vec<ChunkBoundary> {
+    let mut boundaries = Vec::new();
+    let mut pending: Vec<usize> = Vec::new();
+    for (idx, segment) in transcript.segments.iter().enumerate() {
+        pending.push(idx);
+        if segment.granularity != Granularity::Word {
+            boundaries.push(close_phrase(transcript, std::mem::take(&mut pending)));
+        }
+    }
+    if !pending.is_empty() {
+        boundaries.push(close_phrase(transcript, pending));
+    }
+    boundaries
+}
+
+fn close_phrase(transcript: &Transcript, ids: Vec<usize>) -> ChunkBoundary {
+    let first = &transcript.segments[ids[0]];
+    let last = &transcript.segments[ids[ids.len() - 1]];
+    ChunkBoundary {
+        start_time: first.start_time,
+        end_time: last.end_time,
+        source_segment_ids: ids,
+    }
+}
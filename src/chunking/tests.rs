@@ -1,5 +1,9 @@
-use super::calculate_chunk_boundaries;
-use crate::types::{ChunkConfig, Granularity, Segment, Transcript};
+use super::{
+    apply_context_tail, apply_overlap, calculate_chunk_boundaries, close_gaps,
+    fixed_chunk_boundaries, natural_pauses, phrase_chunk_boundaries,
+};
+use crate::audio::slicer::slice_audio_iter;
+use crate::types::{AudioData, ChunkBoundary, ChunkConfig, Granularity, Segment, Transcript};
 
 #[test]
 fn test_basic_chunking() {
@@ -145,3 +149,234 @@ fn test_prefers_pause_boundaries() {
     assert!((boundaries[1].end_time - 2.3).abs() < 1e-9);
     assert_eq!(boundaries[1].source_segment_ids, vec![1]);
 }
+
+#[test]
+fn test_fixed_boundaries_are_evenly_spaced() {
+    let boundaries = fixed_chunk_boundaries(5.0, 2.0);
+
+    let ranges: Vec<(f64, f64)> = boundaries
+        .iter()
+        .map(|boundary| (boundary.start_time, boundary.end_time))
+        .collect();
+    assert_eq!(ranges, vec![(0.0, 2.0), (2.0, 4.0), (4.0, 5.0)]);
+    assert!(boundaries
+        .iter()
+        .all(|boundary| boundary.source_segment_ids.is_empty()));
+}
+
+#[test]
+fn test_fixed_strategy_tiles_whole_audio() {
+    let boundaries = fixed_chunk_boundaries(10.0, 2.0);
+
+    assert_eq!(boundaries.len(), 5);
+    assert!((boundaries[4].start_time - 8.0).abs() < 1e-9);
+    assert!((boundaries[4].end_time - 10.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_fixed_strategy_merges_short_final_chunk() {
+    let boundaries = fixed_chunk_boundaries(10.5, 2.0);
+
+    assert_eq!(boundaries.len(), 5);
+    assert!((boundaries[4].start_time - 8.0).abs() < 1e-9);
+    assert!((boundaries[4].end_time - 10.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_overlap_extends_start_into_previous_chunk() {
+    let transcript = Transcript {
+        segments: vec![
+            Segment {
+                text: "First phrase".to_string(),
+                start_time: 0.0,
+                end_time: 2.0,
+                granularity: Granularity::Sentence,
+            },
+            Segment {
+                text: "Second phrase".to_string(),
+                start_time: 2.0,
+                end_time: 4.0,
+                granularity: Granularity::Sentence,
+            },
+        ],
+    };
+    let boundaries = calculate_chunk_boundaries(&transcript, ChunkConfig::new(2.0), &[]);
+
+    let overlapped = apply_overlap(&boundaries, 0.5, &transcript);
+
+    assert_eq!(overlapped.len(), 2);
+    assert!((overlapped[0].start_time - 0.0).abs() < 1e-9);
+    assert!((overlapped[1].start_time - 1.5).abs() < 1e-9);
+    assert!((overlapped[1].end_time - 4.0).abs() < 1e-9);
+    assert_eq!(overlapped[0].source_segment_ids, vec![0]);
+    assert_eq!(overlapped[1].source_segment_ids, vec![0, 1]);
+
+    let audio = AudioData {
+        samples: (0..4000).map(|i| i as f32).collect(),
+        sample_rate: 1000,
+    };
+    let chunks: Vec<_> = slice_audio_iter(&audio, &overlapped).collect();
+    let shared = &chunks[0].samples[1500..];
+    assert_eq!(shared.len(), 500);
+    assert_eq!(shared, &chunks[1].samples[..500]);
+}
+
+#[test]
+fn test_context_tail_includes_next_word_without_owning_it() {
+    let words = ["uno", "dos", "tres", "cuatro"];
+    let transcript = Transcript {
+        segments: words
+            .iter()
+            .enumerate()
+            .map(|(idx, word)| Segment {
+                text: word.to_string(),
+                start_time: idx as f64,
+                end_time: idx as f64 + 0.8,
+                granularity: Granularity::Word,
+            })
+            .collect(),
+    };
+    let mut boundaries = fixed_chunk_boundaries(4.0, 2.0);
+    boundaries[0].source_segment_ids = vec![0, 1];
+    boundaries[1].source_segment_ids = vec![2, 3];
+
+    let extended = apply_context_tail(&boundaries, 1, &transcript);
+
+    assert!((extended[0].end_time - 2.8).abs() < 1e-9);
+    assert_eq!(extended[0].source_segment_ids, vec![0, 1]);
+    assert!((extended[1].end_time - 4.0).abs() < 1e-9);
+
+    let audio = AudioData {
+        samples: (0..4000).map(|i| i as f32).collect(),
+        sample_rate: 1000,
+    };
+    let chunks: Vec<_> = slice_audio_iter(&audio, &extended).collect();
+    assert_eq!(chunks[0].samples.len(), 2800);
+    assert_eq!(&chunks[0].samples[2000..], &audio.samples[2000..2800]);
+}
+
+#[test]
+fn test_context_tail_stops_at_a_following_sentence() {
+    let segment = |text: &str, start_time: f64, end_time: f64, granularity| Segment {
+        text: text.to_string(),
+        start_time,
+        end_time,
+        granularity,
+    };
+    let transcript = Transcript {
+        segments: vec![
+            segment("hola", 0.0, 0.8, Granularity::Word),
+            segment(
+                "y luego fuimos a la playa todo el día",
+                2.0,
+                14.0,
+                Granularity::Sentence,
+            ),
+            segment("adiós", 14.0, 14.6, Granularity::Word),
+        ],
+    };
+    let boundaries = vec![ChunkBoundary {
+        start_time: 0.0,
+        end_time: 2.0,
+        source_segment_ids: vec![0],
+    }];
+
+    let extended = apply_context_tail(&boundaries, 2, &transcript);
+
+    assert!((extended[0].end_time - 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_close_gaps_absorbs_pauses_into_preceding_boundary() {
+    let boundary = |start_time: f64, end_time: f64| ChunkBoundary {
+        start_time,
+        end_time,
+        source_segment_ids: Vec::new(),
+    };
+    let boundaries = vec![boundary(0.0, 1.5), boundary(2.0, 3.0), boundary(3.0, 4.2)];
+
+    let closed = close_gaps(&boundaries);
+
+    let ranges: Vec<(f64, f64)> = closed.iter().map(|b| (b.start_time, b.end_time)).collect();
+    assert_eq!(ranges, vec![(0.0, 2.0), (2.0, 3.0), (3.0, 4.2)]);
+}
+
+#[test]
+fn test_overlap_never_reaches_before_previous_start() {
+    let boundaries = fixed_chunk_boundaries(3.0, 1.0);
+    let transcript = Transcript {
+        segments: Vec::new(),
+    };
+
+    let overlapped = apply_overlap(&boundaries, 5.0, &transcript);
+
+    let starts: Vec<f64> = overlapped.iter().map(|b| b.start_time).collect();
+    assert_eq!(starts, vec![0.0, 0.0, 1.0]);
+}
+
+#[test]
+fn test_phrase_chunks_close_at_phrase_segments() {
+    let segment = |text: &str, start_time: f64, end_time: f64, granularity| Segment {
+        text: text.to_string(),
+        start_time,
+        end_time,
+        granularity,
+    };
+    let transcript = Transcript {
+        segments: vec![
+            segment("Well", 0.0, 0.3, Granularity::Word),
+            segment("I think so", 0.3, 1.2, Granularity::Phrase),
+            segment(
+                "and then we left the house",
+                1.5,
+                4.0,
+                Granularity::Sentence,
+            ),
+            segment("okay", 4.2, 4.5, Granularity::Word),
+        ],
+    };
+
+    let boundaries = phrase_chunk_boundaries(&transcript);
+
+    let ids: Vec<Vec<usize>> = boundaries
+        .iter()
+        .map(|b| b.source_segment_ids.clone())
+        .collect();
+    assert_eq!(ids, vec![vec![0, 1], vec![2], vec![3]]);
+    assert!((boundaries[0].start_time - 0.0).abs() < 1e-9);
+    assert!((boundaries[0].end_time - 1.2).abs() < 1e-9);
+    assert!((boundaries[2].end_time - 4.5).abs() < 1e-9);
+}
+
+#[test]
+fn test_natural_pauses_measure_gap_after_last_segment() {
+    let segment = |text: &str, start_time: f64, end_time: f64| Segment {
+        text: text.to_string(),
+        start_time,
+        end_time,
+        granularity: Granularity::Phrase,
+    };
+    let transcript = Transcript {
+        segments: vec![
+            segment("first", 0.0, 1.0),
+            segment("second", 1.1, 2.0),
+            segment("third", 2.8, 3.5),
+        ],
+    };
+    let boundary = |ids: Vec<usize>| ChunkBoundary {
+        start_time: 0.0,
+        end_time: 0.0,
+        source_segment_ids: ids,
+    };
+    let boundaries = vec![
+        boundary(vec![0, 1]),
+        boundary(vec![2]),
+        boundary(Vec::new()),
+    ];
+
+    let pauses = natural_pauses(&boundaries, &transcript);
+
+    assert!((pauses[0].unwrap() - 0.8).abs() < 1e-9);
+    assert_eq!(pauses[1], None);
+    assert_eq!(pauses[2], None);
+}
@@ -0,0 +1,254 @@
+//! Deck comparison - report which chunks differ between two output directories
+//!
+//! Chunks are matched by their directory path relative to each deck root, with
+//! the chunk number parsed so decks padded differently (`chunk_7` vs `chunk_0007`)
+//! still line up.
+
+use crate::audio::decoder::{decode_audio, DecodeSettings};
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const CHUNK_FILE: &str = "processed.wav";
+
+/// RMS differences at or below this are treated as identical (16-bit rounding)
+const RMS_TOLERANCE: f64 = 1e-4;
+
+/// Compare two generated decks chunk by chunk
+#[derive(clap::Args, Debug, Clone)]
+pub struct CompareArgs {
+    /// Baseline deck directory
+    #[arg(value_name = "DIR_A")]
+    pub dir_a: PathBuf,
+
+    /// Deck directory to compare against the baseline
+    #[arg(value_name = "DIR_B")]
+    pub dir_b: PathBuf,
+}
+
+/// Length and loudness of a single chunk file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChunkStats {
+    pub samples: usize,
+    pub rms: f64,
+}
+
+/// How a chunk differs between the two decks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChunkStatus {
+    Unchanged,
+    Changed { a: ChunkStats, b: ChunkStats },
+    Added,
+    Removed,
+}
+
+/// Comparison result for one chunk directory
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChunkDiff {
+    pub name: String,
+    pub status: ChunkStatus,
+}
+
+/// Run the compare subcommand and print a per-chunk report
+pub fn run(args: CompareArgs) -> Result<()> {
+    let diffs = compare_decks(&args.dir_a, &args.dir_b)?;
+    for diff in diffs.iter().filter(|d| d.status != ChunkStatus::Unchanged) {
+        println!("{}", describe(diff));
+    }
+    let unchanged = diffs
+        .iter()
+        .filter(|d| d.status == ChunkStatus::Unchanged)
+        .count();
+    println!(
+        "{} chunks compared: {} unchanged, {} differ",
+        diffs.len(),
+        unchanged,
+        diffs.len() - unchanged
+    );
+    Ok(())
+}
+
+/// A chunk directory's identity across decks: its relative path split around the
+/// last run of digits, which is compared as a number rather than as text
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct ChunkKey {
+    prefix: String,
+    number: Option<u64>,
+    suffix: String,
+}
+
+impl ChunkKey {
+    fn parse(name: &str) -> Self {
+        let digits_end = name.trim_end_matches(|c: char| !c.is_ascii_digit()).len();
+        let digits_start = name[..digits_end]
+            .trim_end_matches(|c: char| c.is_ascii_digit())
+            .len();
+        match name[digits_start..digits_end].parse() {
+            Ok(number) => ChunkKey {
+                prefix: name[..digits_start].to_string(),
+                number: Some(number),
+                suffix: name[digits_end..].to_string(),
+            },
+            Err(_) => ChunkKey {
+                prefix: name.to_string(),
+                number: None,
+                suffix: String::new(),
+            },
+        }
+    }
+}
+
+/// Match chunk directories across both decks and classify each one
+pub fn compare_decks(dir_a: &Path, dir_b: &Path) -> Result<Vec<ChunkDiff>> {
+    let chunks_a = list_chunks(dir_a)?;
+    let chunks_b = list_chunks(dir_b)?;
+    let keys: BTreeSet<&ChunkKey> = chunks_a.keys().chain(chunks_b.keys()).collect();
+    keys.into_iter()
+        .map(|key| {
+            let (a, b) = (chunks_a.get(key), chunks_b.get(key));
+            let status = match (a, b) {
+                (Some((_, a)), Some((_, b))) => compare_chunk(a, b)?,
+                (Some(_), None) => ChunkStatus::Removed,
+                _ => ChunkStatus::Added,
+            };
+            let name = a.or(b).map(|(name, _)| name.clone()).unwrap_or_default();
+            Ok(ChunkDiff { name, status })
+        })
+        .collect()
+}
+
+fn compare_chunk(path_a: &Path, path_b: &Path) -> Result<ChunkStatus> {
+    let a = chunk_stats(path_a)?;
+    let b = chunk_stats(path_b)?;
+    if a.samples == b.samples && (a.rms - b.rms).abs() <= RMS_TOLERANCE {
+        Ok(ChunkStatus::Unchanged)
+    } else {
+        Ok(ChunkStatus::Changed { a, b })
+    }
+}
+
+fn chunk_stats(path: &Path) -> Result<ChunkStats> {
+    let audio = decode_audio(path, &DecodeSettings::default())
+        .with_context(|| format!("Failed to decode chunk {:?}", path))?;
+    let energy: f64 = audio.samples.iter().map(|&s| (s as f64) * (s as f64)).sum();
+    let rms = if audio.samples.is_empty() {
+        0.0
+    } else {
+        (energy / audio.samples.len() as f64).sqrt()
+    };
+    Ok(ChunkStats {
+        samples: audio.samples.len(),
+        rms,
+    })
+}
+
+/// Every `processed.wav` under `root`, keyed by its directory relative to `root`
+/// and paired with that directory's name
+fn list_chunks(root: &Path) -> Result<BTreeMap<ChunkKey, (String, PathBuf)>> {
+    let mut chunks = BTreeMap::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir)
+            .with_context(|| format!("Failed to read deck directory {:?}", dir))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if path.file_name().is_some_and(|name| name == CHUNK_FILE) {
+                let relative = dir.strip_prefix(root).unwrap_or(&dir);
+                let name = relative.to_string_lossy().into_owned();
+                chunks.insert(ChunkKey::parse(&name), (name, path));
+            }
+        }
+    }
+    Ok(chunks)
+}
+
+fn describe(diff: &ChunkDiff) -> String {
+    match diff.status {
+        ChunkStatus::Changed { a, b } => format!(
+            "changed  {}: {} → {} samples, RMS {:.4} → {:.4}",
+            diff.name, a.samples, b.samples, a.rms, b.rms
+        ),
+        ChunkStatus::Added => format!("added    {}", diff.name),
+        ChunkStatus::Removed => format!("removed  {}", diff.name),
+        ChunkStatus::Unchanged => format!("same     {}", diff.name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::encoder::{encode_audio, ChannelsOut};
+    use crate::types::AudioData;
+
+    fn write_deck(root: &Path, chunks: &[(&str, f32, usize)]) {
+        for &(name, level, len) in chunks {
+            let dir = root.join(name);
+            fs::create_dir_all(&dir).unwrap();
+            let audio = AudioData {
+                samples: vec![level; len],
+                sample_rate: 8_000,
+            };
+            encode_audio(&audio, dir.join(CHUNK_FILE), ChannelsOut::Same).unwrap();
+        }
+    }
+
+    #[test]
+    fn reports_changed_added_and_removed_chunks() {
+        let root = std::env::temp_dir().join(format!("flowalyzer_compare_{}", std::process::id()));
+        let (dir_a, dir_b) = (root.join("a"), root.join("b"));
+        write_deck(
+            &dir_a,
+            &[
+                ("chunk_0001", 0.2, 800),
+                ("chunk_0002", 0.2, 800),
+                ("chunk_0003", 0.2, 800),
+            ],
+        );
+        write_deck(
+            &dir_b,
+            &[
+                ("chunk_0001", 0.2, 800),
+                ("chunk_0002", 0.5, 800),
+                ("chunk_0004", 0.2, 800),
+            ],
+        );
+
+        let diffs = compare_decks(&dir_a, &dir_b).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        let statuses: Vec<(&str, ChunkStatus)> =
+            diffs.iter().map(|d| (d.name.as_str(), d.status)).collect();
+        assert_eq!(statuses[0], ("chunk_0001", ChunkStatus::Unchanged));
+        assert!(matches!(
+            statuses[1],
+            ("chunk_0002", ChunkStatus::Changed { .. })
+        ));
+        assert_eq!(statuses[2], ("chunk_0003", ChunkStatus::Removed));
+        assert_eq!(statuses[3], ("chunk_0004", ChunkStatus::Added));
+    }
+
+    #[test]
+    fn matches_chunks_by_number_across_pad_widths() {
+        let root =
+            std::env::temp_dir().join(format!("flowalyzer_compare_pad_{}", std::process::id()));
+        let (dir_a, dir_b) = (root.join("a"), root.join("b"));
+        write_deck(&dir_a, &[("chunk_9", 0.2, 800), ("chunk_10", 0.2, 800)]);
+        write_deck(&dir_b, &[("chunk_09", 0.2, 800), ("chunk_10", 0.2, 800)]);
+
+        let diffs = compare_decks(&dir_a, &dir_b).unwrap();
+        fs::remove_dir_all(&root).unwrap();
+
+        let statuses: Vec<(&str, ChunkStatus)> =
+            diffs.iter().map(|d| (d.name.as_str(), d.status)).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ("chunk_9", ChunkStatus::Unchanged),
+                ("chunk_10", ChunkStatus::Unchanged)
+            ]
+        );
+    }
+}
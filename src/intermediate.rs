@@ -0,0 +1,98 @@
+//! Intermediate artifacts - persist decoded audio and transcript between runs
+//!
+//! Dumping after decode/transcription lets chunking and recipe stages be
+//! re-run from disk without repeating the expensive upstream work.
+
+use crate::types::{AudioData, Transcript};
+use anyhow::{ensure, Context, Result};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const AUDIO_FILE: &str = "audio.wav";
+const TRANSCRIPT_FILE: &str = "transcript.json";
+const SOURCE_FILE: &str = "source.json";
+
+/// The input a dump was decoded from, and where its trimmed audio starts in that input
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Source {
+    pub input: PathBuf,
+    pub offset: f64,
+}
+
+/// Write `audio.wav`, `transcript.json` and `source.json` under `dir`, creating it if needed.
+///
+/// The audio is stored as 32-bit float so it reloads sample-for-sample.
+pub fn dump_intermediate(
+    dir: &Path,
+    source: &Source,
+    audio: &AudioData,
+    transcript: &Transcript,
+) -> Result<()> {
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create intermediate directory {:?}", dir))?;
+    write_wav(&dir.join(AUDIO_FILE), audio)?;
+    write_json(&dir.join(TRANSCRIPT_FILE), transcript)?;
+    write_json(&dir.join(SOURCE_FILE), source)
+}
+
+/// Read the audio and transcript previously written by `dump_intermediate`
+pub fn load_intermediate(dir: &Path) -> Result<(AudioData, Transcript)> {
+    let audio = read_wav(&dir.join(AUDIO_FILE))?;
+    let transcript = read_json(&dir.join(TRANSCRIPT_FILE))?;
+    Ok((audio, transcript))
+}
+
+/// Read which input a dump came from, without loading its audio
+pub fn read_source(dir: &Path) -> Result<Source> {
+    read_json(&dir.join(SOURCE_FILE))
+}
+
+fn write_wav(path: &Path, audio: &AudioData) -> Result<()> {
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: audio.sample_rate,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(path, spec)
+        .with_context(|| format!("Failed to create {:?}", path))?;
+    for &sample in &audio.samples {
+        writer
+            .write_sample(sample)
+            .with_context(|| format!("Failed to write {:?}", path))?;
+    }
+    writer
+        .finalize()
+        .with_context(|| format!("Failed to finalize {:?}", path))
+}
+
+fn read_wav(path: &Path) -> Result<AudioData> {
+    let reader =
+        hound::WavReader::open(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let spec = reader.spec();
+    ensure!(
+        spec.channels == 1 && spec.sample_format == hound::SampleFormat::Float,
+        "{:?} is not a mono float WAV written by --dump-intermediate",
+        path
+    );
+    let samples = reader
+        .into_samples::<f32>()
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("Failed to parse {:?}", path))?;
+    Ok(AudioData {
+        samples,
+        sample_rate: spec.sample_rate,
+    })
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let data =
+        serde_json::to_string(value).with_context(|| format!("Failed to serialize {:?}", path))?;
+    fs::write(path, data).with_context(|| format!("Failed to write {:?}", path))
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let data = fs::read_to_string(path).with_context(|| format!("Failed to read {:?}", path))?;
+    serde_json::from_str(&data).with_context(|| format!("Failed to parse {:?}", path))
+}
@@ -0,0 +1,128 @@
+//! Run logging - human-readable progress, silence, or line-delimited JSON events
+//!
+//! Prose progress goes through `progress!`, which is muted in quiet and JSON
+//! modes. Machine-readable events go through `EventLog`, which only writes in
+//! JSON mode so stdout stays parseable.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static HUMAN_OUTPUT: AtomicBool = AtomicBool::new(true);
+
+/// How the pipeline reports progress
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogMode {
+    /// Banner and progress prose on stdout
+    Human,
+    /// No banner or progress output
+    Quiet,
+    /// One JSON event per line on stdout
+    Json,
+}
+
+/// Select the process-wide mode used by `progress!`
+pub fn set_mode(mode: LogMode) {
+    HUMAN_OUTPUT.store(mode == LogMode::Human, Ordering::Relaxed);
+}
+
+/// Whether prose progress should be printed
+pub fn human_output() -> bool {
+    HUMAN_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// `println!` that is silenced outside human log mode
+macro_rules! progress {
+    ($($arg:tt)*) => {
+        if $crate::logging::human_output() {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// A structured pipeline event
+#[derive(Debug, Clone, Serialize)]
+pub struct Event {
+    pub stage: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunk_index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+}
+
+impl Event {
+    pub fn stage(stage: &'static str) -> Self {
+        Self {
+            stage,
+            chunk_index: None,
+            path: None,
+        }
+    }
+
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    pub fn with_chunk(mut self, index: usize) -> Self {
+        self.chunk_index = Some(index);
+        self
+    }
+}
+
+/// Writes events as line-delimited JSON when enabled, otherwise drops them
+pub struct EventLog<W: Write> {
+    out: Option<W>,
+}
+
+impl<W: Write> EventLog<W> {
+    pub fn new(mode: LogMode, out: W) -> Self {
+        Self {
+            out: (mode == LogMode::Json).then_some(out),
+        }
+    }
+
+    pub fn emit(&mut self, event: Event) -> Result<()> {
+        let Some(out) = self.out.as_mut() else {
+            return Ok(());
+        };
+        let line = serde_json::to_string(&event).context("Failed to serialize log event")?;
+        writeln!(out, "{}", line).context("Failed to write log event")
+    }
+
+    #[cfg(test)]
+    pub fn into_inner(self) -> Option<W> {
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_mode_writes_one_event_per_line() {
+        let mut log = EventLog::new(LogMode::Json, Vec::new());
+        log.emit(Event::stage("start").with_path("in.wav")).unwrap();
+        log.emit(Event::stage("chunk_written").with_chunk(3))
+            .unwrap();
+        let output = String::from_utf8(log.into_inner().unwrap()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                r#"{"stage":"start","path":"in.wav"}"#,
+                r#"{"stage":"chunk_written","chunk_index":3}"#
+            ]
+        );
+    }
+
+    #[test]
+    fn other_modes_drop_events() {
+        let mut log = EventLog::new(LogMode::Quiet, Vec::new());
+        log.emit(Event::stage("start")).unwrap();
+        assert!(log.into_inner().is_none());
+    }
+}
@@ -1,30 +1,73 @@
+mod anki;
 mod audio;
+mod batch;
+mod cancel;
 mod chunking;
+mod compare;
+mod intermediate;
+#[macro_use]
+mod logging;
+mod manifest;
 mod operations;
+mod output_template;
+mod single_file;
 mod transcription;
 mod types;
 
 use anyhow::{anyhow, bail, ensure, Context, Result};
+use audio::decoder::{DecodeSettings, DownmixMode};
+use audio::encoder::ChannelsOut;
+use audio::tags::AudioTags;
+use cancel::CancelFlag;
 use clap::Parser;
+use logging::{Event, EventLog, LogMode};
+use output_template::OutputTemplate;
+use serde::Serialize;
 use std::path::PathBuf;
 use std::{fs, path::Path};
 use transcription::TranscriptionSettings;
 
+/// Command line: process inputs into chunk decks, or run a subcommand
+#[derive(Parser, Debug)]
+#[command(name = "flowalyzer", allow_missing_positional = true)]
+#[command(version = "0.1.0")]
+#[command(about = "Audio chunking and manipulation tool", long_about = None)]
+#[command(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    #[command(flatten)]
+    args: Option<Args>,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Diff two generated decks chunk by chunk (use `./compare` for an input named compare)
+    Compare(compare::CompareArgs),
+}
+
 /// Flowalyzer - Audio chunking and manipulation tool
 ///
 /// Processes audio files by breaking them into chunks at linguistic boundaries
 /// and applying operations (repeat, speed change, silence insertion).
-#[derive(Parser, Debug)]
-#[command(name = "flowalyzer")]
-#[command(version = "0.1.0")]
-#[command(about = "Audio chunking and manipulation tool", long_about = None)]
+#[derive(clap::Args, Debug, Clone, Serialize)]
 struct Args {
-    /// Input audio file path (supports MP3, OGG, FLAC, WAV, etc.)
-    #[arg(value_name = "INPUT")]
-    input_file: PathBuf,
-
-    /// Output directory where processed chunk files will be written
+    /// Input audio file paths (supports MP3, OGG, FLAC, WAV, etc.); several files
+    /// run as a batch, each under its own `{stem}` output directory
+    #[arg(
+        value_name = "INPUT",
+        required_unless_present = "from_intermediate",
+        num_args = 1..
+    )]
+    #[serde(skip)]
+    input_files: Vec<PathBuf>,
+
+    /// Output directory where processed chunk files will be written.
+    /// Supports `{stem}` (input basename) and `{n}`/`{n:04}` (chunk number);
+    /// without `{n}`, chunks go to `chunk_N` subdirectories
     #[arg(value_name = "OUTPUT_DIR")]
+    #[serde(skip)]
     output_dir: PathBuf,
 
     /// Target chunk duration in seconds (for linguistic boundary detection)
@@ -33,10 +76,12 @@ struct Args {
 
     /// JSON recipe specification (inline JSON string)
     #[arg(long, value_name = "JSON", conflicts_with = "recipe_file")]
+    #[serde(skip)]
     recipe_json: Option<String>,
 
     /// Path to JSON recipe specification
     #[arg(long, value_name = "PATH", conflicts_with = "recipe_json")]
+    #[serde(skip)]
     recipe_file: Option<PathBuf>,
 
     /// Optional trim start time (seconds or HH:MM:SS.mmm)
@@ -54,19 +99,307 @@ struct Args {
     /// Override language detection with an explicit language code (e.g., 'es')
     #[arg(long, value_name = "LANG")]
     whisper_language: Option<String>,
+
+    /// Sample rate audio is resampled to before transcription; whisper.cpp reads its
+    /// input as 16000 Hz, so that is the only rate accepted
+    #[arg(long, value_name = "HZ")]
+    whisper_sample_rate: Option<u32>,
+
+    /// Number of threads Whisper uses (defaults to available CPU parallelism)
+    #[arg(long, value_name = "N")]
+    whisper_threads: Option<usize>,
+
+    /// Initial prompt biasing Whisper toward expected vocabulary (names, jargon)
+    #[arg(long, value_name = "TEXT")]
+    whisper_prompt: Option<String>,
+
+    /// Initial Whisper sampling temperature (Whisper falls back to higher values on failure)
+    #[arg(long, value_name = "TEMP")]
+    whisper_temperature: Option<f32>,
+
+    /// No-speech probability threshold (0.0-1.0) for treating a window as silence
+    #[arg(long, value_name = "PROB")]
+    whisper_no_speech_threshold: Option<f32>,
+
+    /// Entropy threshold above which Whisper retries at a higher temperature
+    #[arg(long, value_name = "ENTROPY")]
+    whisper_entropy_threshold: Option<f32>,
+
+    /// Merge a Whisper segment that overlaps the previous one into it, instead of
+    /// trimming its start to where the previous segment ends
+    #[arg(long)]
+    whisper_merge_overlaps: bool,
+
+    /// Decode with beam search of this width, overriding the model-size default
+    #[arg(long, value_name = "N")]
+    whisper_beam_size: Option<usize>,
+
+    /// Decode greedily keeping the best of N candidates, overriding the model-size default
+    #[arg(long, value_name = "N", conflicts_with = "whisper_beam_size")]
+    whisper_best_of: Option<usize>,
+
+    /// Beam-search patience (1.0 = standard beam search)
+    #[arg(long, value_name = "FACTOR")]
+    whisper_patience: Option<f32>,
+
+    /// Shortest segment, in seconds, classified as a phrase rather than a word
+    #[arg(long, value_name = "SECONDS", default_value_t = transcription::DEFAULT_PHRASE_BAND.start)]
+    phrase_min_seconds: f64,
+
+    /// Shortest segment, in seconds, classified as a sentence rather than a phrase
+    #[arg(long, value_name = "SECONDS", default_value_t = transcription::DEFAULT_PHRASE_BAND.end)]
+    phrase_max_seconds: f64,
+
+    /// Warn when more than this fraction of decoded samples are clipped (0.0-1.0)
+    #[arg(long, value_name = "FRACTION", default_value_t = 0.001)]
+    clip_warn_fraction: f64,
+
+    /// How to fold multi-channel input into mono
+    #[arg(long, value_enum, default_value_t = DownmixMode::Average)]
+    downmix: DownmixMode,
+
+    /// Abort when an input decodes to more than this many minutes of audio
+    #[arg(long, value_name = "MINUTES")]
+    max_duration_minutes: Option<f64>,
+
+    /// Fail on truncated or corrupt input instead of warning and keeping the partial audio
+    #[arg(long)]
+    strict: bool,
+
+    /// Slice into back-to-back chunks of this many seconds, skipping transcription
+    #[arg(long, value_name = "SECONDS")]
+    fixed_duration: Option<f64>,
+
+    /// Break chunks at phrase boundaries (one chunk per spoken phrase) instead of
+    /// grouping segments toward the target duration
+    #[arg(long, conflicts_with = "fixed_duration")]
+    phrase_chunks: bool,
+
+    /// Seconds each chunk reaches back into the previous chunk
+    #[arg(long, value_name = "SECONDS", default_value_t = 0.0)]
+    overlap: f64,
+
+    /// Extend each chunk's end to include up to this many following word segments
+    /// as listening context (not counted as the chunk's own text); a following
+    /// phrase or sentence segment ends the tail
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    context_tail_words: usize,
+
+    /// Level below which audio counts as silence, in dBFS (e.g. -45); applies to pause
+    /// detection, the only silence check this pipeline runs
+    #[arg(long, value_name = "DB", allow_hyphen_values = true)]
+    trim_silence_db: Option<f64>,
+
+    /// Band-pass audio to the speech range (300-3400 Hz) before pause detection,
+    /// so low-frequency rumble or HVAC noise doesn't hide pauses
+    #[arg(long)]
+    speech_band_vad: bool,
+
+    /// Fall back to fixed-duration chunks when transcription finds no speech
+    #[arg(long)]
+    fallback_fixed_chunks: bool,
+
+    /// Write the decoded audio (float WAV), transcript and input path into this directory
+    #[arg(long, value_name = "DIR", conflicts_with = "from_intermediate")]
+    #[serde(skip)]
+    dump_intermediate: Option<PathBuf>,
+
+    /// Write the trimmed, resampled mono audio handed to Whisper to this WAV, to audition
+    /// exactly what the model heard
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["from_intermediate", "fixed_duration"]
+    )]
+    #[serde(skip)]
+    keep_intermediate_wav: Option<PathBuf>,
+
+    /// Resume from a --dump-intermediate directory instead of decoding and
+    /// transcribing; INPUT and the trim come from the dump, so omit INPUT and --start/--end
+    #[arg(long, value_name = "DIR")]
+    from_intermediate: Option<PathBuf>,
+
+    /// Channel layout for written chunk files
+    #[arg(long, value_enum, default_value_t = ChannelsOut::Same)]
+    channels_out: ChannelsOut,
+
+    /// In the manifest, extend each chunk's source range to the next chunk's start
+    /// so ranges cover the source without gaps (rendered audio is unchanged)
+    #[arg(long)]
+    gapless_source_ranges: bool,
+
+    /// Resample written audio to this rate (e.g. 48000) instead of the source rate
+    #[arg(long, value_name = "HZ")]
+    output_rate: Option<u32>,
+
+    /// Copy the input's title/artist/album tags onto every written WAV; chunk titles
+    /// get a " (n)" suffix and the chunk number as track
+    #[arg(long)]
+    copy_source_tags: bool,
+
+    /// Maximum number of input files processed concurrently in batch mode
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    #[serde(skip)]
+    max_parallel_files: usize,
+
+    /// Skip inputs whose `<stem>.manifest.json` matches the input and current settings
+    #[arg(long)]
+    #[serde(skip)]
+    resume: bool,
+
+    /// With --resume, reprocess every input even if its manifest matches
+    #[arg(long, requires = "resume")]
+    #[serde(skip)]
+    force: bool,
+
+    /// After writing, export an Anki-importable CSV (transcript, [sound:path]) of every chunk
+    #[arg(long, value_name = "PATH")]
+    #[serde(skip)]
+    anki_csv: Option<PathBuf>,
+
+    /// Time-stretch algorithm for recipe speed changes
+    #[arg(long, value_enum, default_value_t = types::SpeedAlgorithm::Signalsmith)]
+    speed_algo: types::SpeedAlgorithm,
+
+    /// Make silent recipe steps last this multiple of the speaker's own pause after
+    /// each chunk (from transcript gaps) instead of the chunk's length
+    #[arg(long = "natural-pauses", value_name = "SCALE")]
+    natural_pause_scale: Option<f64>,
+
+    /// Mix a quiet bed under every audio repeat: `self` for the chunk itself at its
+    /// original speed, or a backing-track file aligned with the input's timeline
+    #[arg(long, value_name = "self|PATH")]
+    mix_reference: Option<PathBuf>,
+
+    /// Level of the --mix-reference bed, in dB relative to full scale
+    #[arg(
+        long,
+        value_name = "DB",
+        default_value_t = -18.0,
+        allow_hyphen_values = true,
+        requires = "mix_reference"
+    )]
+    mix_gain_db: f32,
+
+    /// Crossfade between repeats and silences within a chunk, in milliseconds;
+    /// 0 concatenates exactly for gapless rhythm drills
+    #[arg(long, value_name = "MS", default_value_t = audio::assembler::DEFAULT_CROSSFADE_MS)]
+    crossfade_ms: f64,
+
+    /// Skip the crossfade between bit-identical neighbouring pieces (e.g. back-to-back
+    /// repeats), avoiding comb filtering; differing neighbours still crossfade
+    #[arg(long)]
+    butt_join_repeats: bool,
+
+    /// Join all processed chunks into this one WAV instead of per-chunk directories,
+    /// with chapter markers written alongside as <PATH>.ffmetadata
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["anki_csv", "resume"])]
+    single_file: Option<PathBuf>,
+
+    /// Silence between chunks in --single-file output, in milliseconds
+    #[arg(
+        long,
+        value_name = "MS",
+        default_value_t = 1000.0,
+        requires = "single_file"
+    )]
+    single_file_gap_ms: f64,
+
+    /// Plan chunks and print the estimated output length and size without writing anything
+    #[arg(long, conflicts_with = "anki_csv")]
+    #[serde(skip)]
+    dry_run: bool,
+
+    /// Save each chunk's transcript text as transcript.txt next to processed.wav
+    #[arg(long)]
+    chunk_text: bool,
+
+    /// Zero-padding width for chunk numbers (default: digits in the chunk count)
+    #[arg(long, value_name = "DIGITS")]
+    pad_width: Option<usize>,
+
+    /// Write an envelope.json of per-bucket min/max and peak amplitudes next to each chunk
+    #[arg(long)]
+    envelopes: bool,
+
+    /// Number of buckets in each --envelopes file
+    #[arg(long, value_name = "COUNT", default_value_t = 256)]
+    envelope_buckets: usize,
+
+    /// Suppress the banner and progress output
+    #[arg(long, conflicts_with = "json_logs")]
+    #[serde(skip)]
+    quiet: bool,
+
+    /// Emit line-delimited JSON events (stage, chunk_index, path) instead of prose
+    #[arg(long)]
+    #[serde(skip)]
+    json_logs: bool,
+}
+
+/// Settings controlling how processed chunks are written
+#[derive(Debug, Clone)]
+struct OutputSettings {
+    channels_out: ChannelsOut,
+    chunk_dirs: OutputTemplate,
+    stem: String,
+    chunk_text: bool,
+    pad_width: Option<usize>,
+    envelope_buckets: Option<usize>,
+    /// Record source ranges in the manifest with inter-chunk pauses absorbed by the preceding chunk
+    gapless_source_ranges: bool,
+    /// Sample rate written audio is converted to; `None` keeps the source rate
+    output_rate: Option<u32>,
+    /// Crossfade between a chunk's rendered pieces, in milliseconds (0 = exact concatenation)
+    crossfade_ms: f64,
+    /// Concatenate identical neighbouring pieces without a crossfade
+    butt_join_repeats: bool,
+    /// Joined track path and inter-chunk gap in seconds, replacing chunk directories
+    single_file: Option<(PathBuf, f64)>,
+    /// Tags read from the input, copied onto written files
+    source_tags: Option<AudioTags>,
+    /// Where the processed audio starts in the input, in seconds (the `--start` trim)
+    source_offset: f64,
+}
+
+impl OutputSettings {
+    /// Directory for chunk `number` (1-based) out of `total`
+    fn chunk_dir(&self, number: usize, total: usize) -> PathBuf {
+        let pad_width = self
+            .pad_width
+            .unwrap_or_else(|| output_template::auto_pad_width(total));
+        self.chunk_dirs.resolve(&self.stem, number, pad_width)
+    }
+
+    /// Folder holding this input's chunk directories and manifest
+    fn source_dir(&self) -> PathBuf {
+        let first_chunk = self.chunk_dirs.resolve(&self.stem, 1, 1);
+        first_chunk
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default()
+    }
+
+    /// This input's manifest, named by stem so inputs sharing a folder keep their own
+    fn manifest_path(&self) -> PathBuf {
+        manifest::manifest_path(&self.source_dir(), &self.stem)
+    }
+}
+
+/// Everything `--resume` compares: output-shaping arguments plus the parsed recipe
+#[derive(Serialize)]
+struct HashedSettings<'a> {
+    #[serde(flatten)]
+    args: &'a Args,
+    recipe: &'a types::RuntimeRecipe,
 }
 
 impl Args {
     /// Validate CLI arguments
     fn validate(&self) -> Result<()> {
-        // Check input file exists
-        if !self.input_file.exists() {
-            anyhow::bail!("Input file does not exist: {:?}", self.input_file);
-        }
-
-        // Check input file is readable
-        if !self.input_file.is_file() {
-            anyhow::bail!("Input path is not a file: {:?}", self.input_file);
+        match &self.from_intermediate {
+            Some(dir) => self.validate_intermediate_dir(dir)?,
+            None => self.validate_input_files()?,
         }
 
         // Check target duration is positive
@@ -77,6 +410,86 @@ impl Args {
             );
         }
 
+        if let Some(duration) = self.fixed_duration {
+            if duration <= 0.0 {
+                anyhow::bail!("Fixed chunk duration must be positive, got: {}", duration);
+            }
+        }
+
+        if self.overlap < 0.0 {
+            anyhow::bail!("Overlap must not be negative, got: {}", self.overlap);
+        }
+
+        if !(0.0..=1.0).contains(&self.clip_warn_fraction) {
+            anyhow::bail!(
+                "Clip warning fraction must be between 0.0 and 1.0, got: {}",
+                self.clip_warn_fraction
+            );
+        }
+
+        if self.max_parallel_files == 0 {
+            anyhow::bail!("--max-parallel-files must be at least 1");
+        }
+
+        if self.is_batch() && self.dump_intermediate.is_some() {
+            anyhow::bail!("--dump-intermediate takes a single INPUT");
+        }
+
+        if self.is_batch() && self.keep_intermediate_wav.is_some() {
+            anyhow::bail!("--keep-intermediate-wav takes a single INPUT");
+        }
+
+        if self.output_rate == Some(0) {
+            anyhow::bail!("Output sample rate must be positive");
+        }
+
+        if let Some(db) = self.trim_silence_db {
+            ensure!(
+                db.is_finite() && db <= 0.0,
+                "Silence threshold must be a finite level at or below 0 dBFS, got: {}",
+                db
+            );
+        }
+
+        if let Some(minutes) = self.max_duration_minutes {
+            if minutes <= 0.0 {
+                anyhow::bail!("Maximum duration must be positive, got: {}", minutes);
+            }
+        }
+
+        if let Some(scale) = self.natural_pause_scale {
+            if scale <= 0.0 {
+                anyhow::bail!("Natural pause scale must be positive, got: {}", scale);
+            }
+        }
+
+        if !self.mix_gain_db.is_finite() {
+            anyhow::bail!("Mix gain must be a finite number of dB");
+        }
+
+        if self.crossfade_ms < 0.0 {
+            anyhow::bail!("Crossfade must not be negative, got: {}", self.crossfade_ms);
+        }
+
+        if self.is_batch() && self.single_file.is_some() {
+            anyhow::bail!("--single-file takes a single INPUT");
+        }
+
+        if self.single_file_gap_ms < 0.0 {
+            anyhow::bail!(
+                "Single-file gap must not be negative, got: {}",
+                self.single_file_gap_ms
+            );
+        }
+
+        if self.pad_width == Some(0) {
+            anyhow::bail!("Pad width must be positive");
+        }
+
+        if self.envelopes && self.envelope_buckets == 0 {
+            anyhow::bail!("Envelope bucket count must be positive");
+        }
+
         if self.recipe_json.is_none() && self.recipe_file.is_none() {
             anyhow::bail!("Provide a recipe via --recipe-json or --recipe-file");
         }
@@ -88,6 +501,49 @@ impl Args {
         Ok(())
     }
 
+    fn validate_input_files(&self) -> Result<()> {
+        for input_file in &self.input_files {
+            // Check input file exists
+            if !input_file.exists() {
+                anyhow::bail!("Input file does not exist: {:?}", input_file);
+            }
+
+            // Check input file is readable
+            if !input_file.is_file() {
+                anyhow::bail!("Input path is not a file: {:?}", input_file);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn validate_intermediate_dir(&self, dir: &Path) -> Result<()> {
+        if !dir.is_dir() {
+            anyhow::bail!("Intermediate directory does not exist: {:?}", dir);
+        }
+        ensure!(
+            self.input_files.is_empty(),
+            "--from-intermediate reads INPUT from the dump; drop {:?}",
+            self.input_files
+        );
+        ensure!(
+            self.start.is_none() && self.end.is_none(),
+            "--start/--end cannot apply to a --from-intermediate dump, which is already trimmed"
+        );
+        Ok(())
+    }
+
+    /// With `--from-intermediate`, take INPUT from the dump so output naming and
+    /// manifests follow the original file
+    fn resolve_intermediate_input(&mut self) -> Result<()> {
+        if let Some(dir) = &self.from_intermediate {
+            let source =
+                intermediate::read_source(dir).context("Failed to read intermediate source")?;
+            self.input_files = vec![source.input];
+        }
+        Ok(())
+    }
+
     fn runtime_recipe(&self) -> Result<types::RuntimeRecipe> {
         load_recipe_from_sources(self.recipe_file.as_deref(), self.recipe_json.as_deref())
     }
@@ -103,6 +559,91 @@ impl Args {
         Ok((start, end))
     }
 
+    fn chunk_strategy(&self) -> types::ChunkStrategy {
+        match self.fixed_duration {
+            Some(duration) => types::ChunkStrategy::Fixed { duration },
+            None if self.phrase_chunks => types::ChunkStrategy::Phrase,
+            None => types::ChunkStrategy::Linguistic,
+        }
+    }
+
+    /// Linear amplitude below which pause detection treats audio as silent
+    fn silence_threshold(&self) -> f32 {
+        self.trim_silence_db.map_or(
+            audio::pause_detector::DEFAULT_SILENCE_THRESHOLD,
+            audio::pause_detector::dbfs_to_amplitude,
+        )
+    }
+
+    fn decode_settings(&self) -> DecodeSettings {
+        DecodeSettings {
+            downmix: self.downmix,
+            strict: self.strict,
+            max_duration: self.max_duration_minutes.map(|minutes| minutes * 60.0),
+        }
+    }
+
+    fn log_mode(&self) -> LogMode {
+        if self.json_logs {
+            LogMode::Json
+        } else if self.quiet {
+            LogMode::Quiet
+        } else {
+            LogMode::Human
+        }
+    }
+
+    /// Hash of every setting that affects output, ignoring which files are in the batch
+    fn settings_hash(&self, recipe: &types::RuntimeRecipe) -> Result<String> {
+        let settings = HashedSettings { args: self, recipe };
+        let json = serde_json::to_string(&settings).context("Failed to serialize settings")?;
+        Ok(manifest::settings_hash(&json))
+    }
+
+    /// Whether `--resume` allows skipping `input`, already processed with these settings
+    fn skip_processed(&self, input: &Path, output: &OutputSettings, settings_hash: &str) -> bool {
+        self.resume
+            && !self.force
+            && manifest::is_up_to_date(&output.manifest_path(), input, settings_hash)
+    }
+
+    fn is_batch(&self) -> bool {
+        self.input_files.len() > 1
+    }
+
+    /// Output settings for one input; batch runs nest under `{stem}` unless the template uses it
+    fn output_settings(&self, input_file: &Path) -> Result<OutputSettings> {
+        let mut chunk_dirs = parse_output_template(&self.output_dir)?;
+        if self.is_batch() && !chunk_dirs.has_stem() {
+            chunk_dirs = parse_output_template(&self.output_dir.join("{stem}"))?;
+        }
+        Ok(OutputSettings {
+            channels_out: self.channels_out,
+            chunk_dirs,
+            stem: source_stem(input_file),
+            chunk_text: self.chunk_text,
+            pad_width: self.pad_width,
+            envelope_buckets: self.envelopes.then_some(self.envelope_buckets),
+            gapless_source_ranges: self.gapless_source_ranges,
+            output_rate: self.output_rate,
+            crossfade_ms: self.crossfade_ms,
+            butt_join_repeats: self.butt_join_repeats,
+            single_file: self
+                .single_file
+                .clone()
+                .map(|path| (path, self.single_file_gap_ms / 1000.0)),
+            source_tags: None,
+            source_offset: 0.0,
+        })
+    }
+
+    /// Tags to copy from `input` onto written files, when `--copy-source-tags` is set
+    fn source_tags(&self, input: &Path) -> Result<Option<AudioTags>> {
+        self.copy_source_tags
+            .then(|| audio::tags::read_tags(input))
+            .transpose()
+    }
+
     fn transcription_settings(&self) -> Result<TranscriptionSettings> {
         let mut settings = TranscriptionSettings::default();
 
@@ -120,85 +661,472 @@ impl Args {
             settings.detect_language = false;
         }
 
+        if let Some(rate) = self.whisper_sample_rate {
+            // Any other rate would be heard time-stretched, skewing every segment timestamp
+            ensure!(
+                rate == transcription::TARGET_SAMPLE_RATE,
+                "Whisper reads audio at {} Hz; --whisper-sample-rate {} would shift every timestamp",
+                transcription::TARGET_SAMPLE_RATE,
+                rate
+            );
+            settings.sample_rate = rate;
+        }
+
+        if let Some(prompt) = &self.whisper_prompt {
+            let trimmed = prompt.trim();
+            ensure!(!trimmed.is_empty(), "Whisper prompt must not be empty");
+            settings.initial_prompt = Some(trimmed.to_string());
+        }
+
+        if let Some(threads) = self.whisper_threads {
+            ensure!(threads > 0, "Whisper thread count must be positive");
+            settings.threads = threads;
+        }
+
+        ensure!(
+            0.0 < self.phrase_min_seconds && self.phrase_min_seconds < self.phrase_max_seconds,
+            "Phrase band must satisfy 0 < min < max, got {}..{}",
+            self.phrase_min_seconds,
+            self.phrase_max_seconds
+        );
+        settings.phrase_band = self.phrase_min_seconds..self.phrase_max_seconds;
+        settings.merge_overlaps = self.whisper_merge_overlaps;
+
+        self.apply_decoding_thresholds(&mut settings)?;
         settings.apply_model_defaults();
 
         Ok(settings)
     }
+
+    fn apply_decoding_thresholds(&self, settings: &mut TranscriptionSettings) -> Result<()> {
+        if let Some(temperature) = self.whisper_temperature {
+            ensure!(
+                temperature >= 0.0,
+                "Whisper temperature must not be negative"
+            );
+            settings.temperature = Some(temperature);
+        }
+        if let Some(threshold) = self.whisper_no_speech_threshold {
+            ensure!(
+                (0.0..=1.0).contains(&threshold),
+                "Whisper no-speech threshold must be between 0.0 and 1.0"
+            );
+            settings.no_speech_threshold = Some(threshold);
+        }
+        if let Some(threshold) = self.whisper_entropy_threshold {
+            ensure!(
+                threshold > 0.0,
+                "Whisper entropy threshold must be positive"
+            );
+            settings.entropy_threshold = Some(threshold);
+        }
+        ensure!(
+            self.whisper_beam_size != Some(0) && self.whisper_best_of != Some(0),
+            "Whisper beam size and best-of must be positive"
+        );
+        settings.beam_size = self.whisper_beam_size;
+        settings.best_of = self.whisper_best_of;
+        if let Some(patience) = self.whisper_patience {
+            ensure!(patience > 0.0, "Whisper patience must be positive");
+            settings.patience = Some(patience);
+        }
+        Ok(())
+    }
+}
+
+fn parse_output_template(output_dir: &Path) -> Result<OutputTemplate> {
+    OutputTemplate::parse(&output_dir.to_string_lossy())
+        .context("Failed to parse output directory template")
+}
+
+fn source_stem(input_file: &Path) -> String {
+    input_file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "output".to_string())
 }
 
 fn main() -> Result<()> {
-    run(Args::parse())
+    let cli = Cli::parse();
+    if let Some(Command::Compare(compare)) = cli.command {
+        return compare::run(compare);
+    }
+    let args = cli
+        .args
+        .ok_or_else(|| anyhow!("INPUT and OUTPUT_DIR are required"))?;
+    match run(args, &CancelFlag::default()) {
+        Err(err) if err.is::<cancel::Cancelled>() => {
+            eprintln!("{}", err);
+            Ok(())
+        }
+        result => result,
+    }
 }
 
-fn run(args: Args) -> Result<()> {
+/// Run the full pipeline over every input, stopping between stages once `cancel` is set
+fn run(mut args: Args, cancel: &CancelFlag) -> Result<()> {
     args.validate()
         .context("Failed to validate command-line arguments")?;
+    args.resolve_intermediate_input()?;
     let transcription_settings = args.transcription_settings()?;
+    let jobs = args
+        .input_files
+        .iter()
+        .map(|input| Ok((input.as_path(), args.output_settings(input)?)))
+        .collect::<Result<Vec<_>>>()?;
+    logging::set_mode(args.log_mode());
     print_banner(&args, &transcription_settings);
-    let recipe = load_recipe(&args)?;
+    let runtime_recipe = args
+        .runtime_recipe()
+        .context("Failed to load recipe specification")?;
+    let recipe = load_recipe(&args, &runtime_recipe)?;
     log_recipe(&recipe);
-    let trim = args.trim_range()?;
-    log_trim_request(trim);
-    let audio = decode_and_trim(&args, trim)?;
-    let transcript = transcribe_with_logging(&audio, &transcription_settings)?;
-    let boundaries = plan_chunks(&audio, &transcript, args.target_duration);
-    let chunks = slice_chunks(&audio, &boundaries);
-    write_chunks(&chunks, &boundaries, &recipe, &args.output_dir)?;
-    println!("\n✓ Processing complete!");
+    let settings_hash = args.settings_hash(&runtime_recipe)?;
+    let results = batch::run_bounded(&jobs, args.max_parallel_files, |(input, output)| {
+        if args.skip_processed(input, output, &settings_hash) {
+            progress!("\nSkipping {:?}: outputs match current settings", input);
+            return Ok(());
+        }
+        let chunks = process_file(
+            &args,
+            input,
+            output,
+            &recipe,
+            &transcription_settings,
+            cancel,
+        )?;
+        if args.dry_run || args.single_file.is_some() {
+            return Ok(());
+        }
+        write_file_manifest(input, output, &settings_hash, chunks)
+    });
+    finish_batch(&args.input_files, results)?;
+    if let Some(path) = &args.anki_csv {
+        export_anki_csv(path, &jobs)?;
+    }
+    progress!("\n✓ Processing complete!");
+    Ok(())
+}
+
+/// Surface a single file's error directly; summarize failures across a batch
+fn finish_batch(inputs: &[PathBuf], results: Vec<Result<()>>) -> Result<()> {
+    if inputs.len() == 1 {
+        return results.into_iter().next().unwrap_or(Ok(()));
+    }
+    let mut failed = 0;
+    for (input, result) in inputs.iter().zip(results) {
+        match result {
+            Err(err) if err.is::<cancel::Cancelled>() => return Err(err),
+            Err(err) => {
+                eprintln!("   Failed to process {:?}: {:#}", input, err);
+                failed += 1;
+            }
+            Ok(()) => {}
+        }
+    }
+    ensure!(
+        failed == 0,
+        "{} of {} input files failed",
+        failed,
+        inputs.len()
+    );
+    Ok(())
+}
+
+fn process_file(
+    args: &Args,
+    input: &Path,
+    output_settings: &OutputSettings,
+    recipe: &types::Recipe,
+    transcription_settings: &TranscriptionSettings,
+    cancel: &CancelFlag,
+) -> Result<Vec<manifest::ChunkEntry>> {
+    let mut events = EventLog::new(args.log_mode(), std::io::stdout());
+    events.emit(Event::stage("start").with_path(input))?;
+    progress!("\nProcessing {:?}", input);
+    let strategy = args.chunk_strategy();
+    let source_tags = args.source_tags(input)?;
+    let (audio, transcript, source_offset) =
+        load_audio_and_transcript(args, input, transcription_settings, strategy, cancel)?;
+    let output_settings = &OutputSettings {
+        source_tags,
+        source_offset,
+        ..output_settings.clone()
+    };
+    cancel::check(cancel)?;
+    events.emit(Event::stage("loaded"))?;
+    let boundaries = plan_for_strategy(&audio, &transcript, strategy, args)?;
+    let boundaries = overlap_chunks(&boundaries, args.overlap, &transcript);
+    let boundaries = context_tail_chunks(&boundaries, args.context_tail_words, &transcript);
+    cancel::check(cancel)?;
+    events.emit(Event::stage("planned"))?;
+    if args.dry_run {
+        let pauses = chunking::natural_pauses(&boundaries, &transcript);
+        log_output_estimate(
+            &boundaries,
+            &pauses,
+            recipe,
+            audio.sample_rate,
+            output_settings,
+        );
+        return Ok(Vec::new());
+    }
+    let chunks = slice_chunks(&audio, &boundaries, source_offset);
+    if let Some((path, _)) = &output_settings.single_file {
+        write_single_track(
+            chunks,
+            &boundaries,
+            &transcript,
+            recipe,
+            output_settings,
+            cancel,
+        )?;
+        events.emit(Event::stage("complete").with_path(path))?;
+        return Ok(Vec::new());
+    }
+    let chunks = write_chunks(
+        chunks,
+        &boundaries,
+        &transcript,
+        recipe,
+        output_settings,
+        &mut events,
+        cancel,
+    )?;
+    log_chunk_summary(chunks.len(), &args.output_dir);
+    events.emit(Event::stage("complete").with_path(&args.output_dir))?;
+    Ok(chunks)
+}
+
+fn log_output_estimate(
+    boundaries: &[types::ChunkBoundary],
+    pauses: &[Option<f64>],
+    recipe: &types::Recipe,
+    sample_rate: u32,
+    settings: &OutputSettings,
+) {
+    let joins = operations::estimate::JoinSettings {
+        crossfade_ms: settings.crossfade_ms,
+        butt_join_repeats: settings.butt_join_repeats,
+        single_file_gap: settings.single_file.as_ref().map(|(_, gap)| *gap),
+    };
+    let estimate = operations::estimate::estimate_recipe_output(
+        boundaries,
+        pauses,
+        recipe,
+        sample_rate,
+        &joins,
+    );
+    let channels = if recipe.pan.is_some() {
+        2
+    } else {
+        settings.channels_out.count()
+    };
+    let bytes = estimate.wav_bytes(settings.output_rate.unwrap_or(sample_rate), channels);
+    progress!("\nDry run: nothing written");
+    progress!(
+        "   {} chunks, ~{:.1}s of rendered audio, ~{:.1} MB as 16-bit WAV",
+        estimate.chunks,
+        estimate.seconds,
+        bytes as f64 / 1_000_000.0
+    );
+}
+
+fn write_file_manifest(
+    input: &Path,
+    output: &OutputSettings,
+    settings_hash: &str,
+    chunks: Vec<manifest::ChunkEntry>,
+) -> Result<()> {
+    let path = output.manifest_path();
+    if let Ok(previous) = manifest::read_manifest(&path) {
+        if previous.input == input {
+            remove_stale_chunks(&output.source_dir(), &previous, &chunks)?;
+        }
+    }
+    let manifest = manifest::Manifest {
+        input: input.to_path_buf(),
+        settings_hash: settings_hash.to_string(),
+        chunks,
+    };
+    manifest::write_manifest(&path, &manifest)
+}
+
+/// Delete chunk folders from a previous run of this input that this run didn't rewrite,
+/// e.g. unpadded names left behind once the chunk count gains a digit
+fn remove_stale_chunks(
+    source_dir: &Path,
+    previous: &manifest::Manifest,
+    chunks: &[manifest::ChunkEntry],
+) -> Result<()> {
+    let stale = manifest::stale_chunk_dirs(previous, chunks);
+    for dir in &stale {
+        let dir = source_dir.join(dir);
+        match fs::remove_dir_all(&dir) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                return Err(err).with_context(|| format!("Failed to remove stale chunk {:?}", dir));
+            }
+            _ => {}
+        }
+    }
+    if !stale.is_empty() {
+        progress!(
+            "   Removed {} stale chunk folder(s) from a previous run",
+            stale.len()
+        );
+    }
+    Ok(())
+}
+
+/// Gather every input's manifest into one Anki CSV, including inputs skipped by --resume
+fn export_anki_csv(path: &Path, jobs: &[(&Path, OutputSettings)]) -> Result<()> {
+    let csv_dir = path.parent().unwrap_or(Path::new(""));
+    let mut notes = Vec::new();
+    for (_, output) in jobs {
+        let manifest = manifest::read_manifest(&output.manifest_path())?;
+        notes.extend(anki::notes_from_manifest(
+            &manifest,
+            &output.source_dir(),
+            csv_dir,
+        ));
+    }
+    anki::write_anki_csv(path, &notes)?;
+    progress!("   Wrote {} Anki notes to {:?}", notes.len(), path);
     Ok(())
 }
 
 fn print_banner(args: &Args, settings: &TranscriptionSettings) {
-    println!("Flowalyzer v0.1.0 - Language Learning Audio Processor");
-    println!("Input:  {:?}", args.input_file);
-    println!("Output dir: {:?}", args.output_dir);
-    println!("Target chunk duration: {} seconds", args.target_duration);
-    println!("Whisper model: {}", settings.model_path);
+    progress!("Flowalyzer v0.1.0 - Language Learning Audio Processor");
+    for input in &args.input_files {
+        progress!("Input:  {:?}", input);
+    }
+    progress!("Output dir: {:?}", args.output_dir);
+    progress!("Target chunk duration: {} seconds", args.target_duration);
+    progress!("Whisper model: {}", settings.model_path);
+    progress!("Whisper input rate: {} Hz", settings.sample_rate);
+    progress!("Whisper threads: {}", settings.threads);
+    if let Some(prompt) = &settings.initial_prompt {
+        progress!("Whisper initial prompt: {}", format_preview_text(prompt));
+    }
     if settings.is_english_only_model()
         && settings.language.as_deref() == Some("en")
         && !settings.detect_language
     {
-        println!("Whisper language: English (model is English-only; detection disabled)");
+        progress!("Whisper language: English (model is English-only; detection disabled)");
     } else {
         match (&settings.language, settings.detect_language) {
-            (Some(language), _) => println!("Whisper language override: {}", language),
-            (None, true) => println!("Whisper language detection: enabled"),
-            (None, false) => println!("Whisper language detection: disabled"),
+            (Some(language), _) => progress!("Whisper language override: {}", language),
+            (None, true) => progress!("Whisper language detection: enabled"),
+            (None, false) => progress!("Whisper language detection: disabled"),
         }
     }
 }
 
-fn load_recipe(args: &Args) -> Result<types::Recipe> {
-    let runtime = args
-        .runtime_recipe()
-        .context("Failed to load recipe specification")?;
+fn load_recipe(args: &Args, runtime: &types::RuntimeRecipe) -> Result<types::Recipe> {
     runtime.validate().context("Recipe validation failed")?;
-    Ok(runtime.to_recipe())
+    let mut recipe = runtime.to_recipe();
+    recipe.speed_algorithm = args.speed_algo;
+    recipe.natural_pause_scale = args.natural_pause_scale;
+    if recipe.pan.is_some() {
+        ensure!(
+            args.single_file.is_none(),
+            "Recipe pan writes stereo chunk files and cannot be joined with --single-file"
+        );
+        ensure!(
+            args.channels_out != ChannelsOut::Mono,
+            "Recipe pan writes stereo chunk files; drop --channels-out mono"
+        );
+    }
+    if let Some(reference) = &args.mix_reference {
+        recipe.mix = Some(types::Mix {
+            bed: load_mix_bed(reference, &args.decode_settings())?,
+            gain_db: args.mix_gain_db,
+        });
+    }
+    Ok(recipe)
+}
+
+/// `self` beds each chunk with itself; anything else is decoded as a backing track
+fn load_mix_bed(reference: &Path, settings: &DecodeSettings) -> Result<types::MixBed> {
+    if reference == Path::new("self") {
+        return Ok(types::MixBed::Chunk);
+    }
+    let audio = audio::decoder::decode_audio(reference, settings)
+        .with_context(|| format!("Failed to decode mix reference {:?}", reference))?;
+    Ok(types::MixBed::Track {
+        audio: std::sync::Arc::new(audio),
+    })
 }
 
 fn log_recipe(recipe: &types::Recipe) {
-    println!("Recipe: {} ({} steps)", recipe.name, recipe.steps.len());
+    progress!("Recipe: {} ({} steps)", recipe.name, recipe.steps.len());
 }
 
 fn log_trim_request(trim: (Option<f64>, Option<f64>)) {
     if let Some(start) = trim.0 {
-        println!("Trim start: {:.3} seconds", start);
+        progress!("Trim start: {:.3} seconds", start);
     }
     if let Some(end) = trim.1 {
-        println!("Trim end: {:.3} seconds", end);
+        progress!("Trim end: {:.3} seconds", end);
+    }
+}
+
+/// Decoded (trimmed) audio, its transcript, and where that audio starts in the input
+fn load_audio_and_transcript(
+    args: &Args,
+    input: &Path,
+    settings: &TranscriptionSettings,
+    strategy: types::ChunkStrategy,
+    cancel: &CancelFlag,
+) -> Result<(types::AudioData, types::Transcript, f64)> {
+    if let Some(dir) = &args.from_intermediate {
+        progress!("\n1-2. Loading decoded audio and transcript from {:?}", dir);
+        let (audio, transcript) = intermediate::load_intermediate(dir)
+            .context("Failed to load intermediate artifacts")?;
+        let source =
+            intermediate::read_source(dir).context("Failed to read intermediate source")?;
+        return Ok((audio, transcript, source.offset));
+    }
+    let trim = args.trim_range()?;
+    log_trim_request(trim);
+    let audio = decode_and_trim(args, input, trim)?;
+    cancel::check(cancel)?;
+    let transcript = transcribe_for_strategy(
+        &audio,
+        settings,
+        strategy,
+        args.keep_intermediate_wav.as_deref(),
+        cancel,
+    )?;
+    let offset = trim.0.unwrap_or(0.0);
+    if let Some(dir) = &args.dump_intermediate {
+        let source = intermediate::Source {
+            input: input.to_path_buf(),
+            offset,
+        };
+        intermediate::dump_intermediate(dir, &source, &audio, &transcript)
+            .context("Failed to dump intermediate artifacts")?;
+        progress!("   Dumped decoded audio and transcript to {:?}", dir);
     }
+    Ok((audio, transcript, offset))
 }
 
-fn decode_and_trim(args: &Args, trim: (Option<f64>, Option<f64>)) -> Result<types::AudioData> {
-    println!("\n1. Decoding input audio...");
-    let decoded =
-        audio::decoder::decode_audio(&args.input_file).context("Failed to decode input audio")?;
-    println!(
+fn decode_and_trim(
+    args: &Args,
+    input: &Path,
+    trim: (Option<f64>, Option<f64>),
+) -> Result<types::AudioData> {
+    progress!("\n1. Decoding input audio...");
+    let mut decoded = audio::decoder::decode_audio(input, &args.decode_settings())
+        .context("Failed to decode input audio")?;
+    progress!(
         "   Loaded {} samples at {} Hz",
         decoded.samples.len(),
         decoded.sample_rate
     );
-    let total_duration = decoded.samples.len() as f64 / decoded.sample_rate as f64;
+    log_sanitized(decoded.sanitize());
+    log_clipping(&decoded, args.clip_warn_fraction);
+    let total_duration = decoded.duration();
     let start = trim.0.unwrap_or(0.0);
     let end = trim.1.unwrap_or(total_duration);
     ensure!(
@@ -220,7 +1148,7 @@ fn decode_and_trim(args: &Args, trim: (Option<f64>, Option<f64>)) -> Result<type
     );
     let effective_end = end.min(total_duration);
     if start > 0.0 || effective_end < total_duration {
-        println!(
+        progress!(
             "   Trimming audio to range {:.3}s - {:.3}s (duration {:.3}s)",
             start,
             effective_end,
@@ -231,18 +1159,72 @@ fn decode_and_trim(args: &Args, trim: (Option<f64>, Option<f64>)) -> Result<type
     Ok(decoded)
 }
 
+fn log_sanitized(replaced: usize) {
+    if replaced > 0 {
+        eprintln!(
+            "   Warning: replaced {} non-finite samples (NaN/Inf) with silence",
+            replaced
+        );
+    }
+}
+
+fn log_clipping(audio: &types::AudioData, warn_fraction: f64) {
+    let fraction = audio::decoder::clip_fraction(&audio.samples);
+    progress!("   Clipped samples: {:.3}%", fraction * 100.0);
+    if fraction > warn_fraction {
+        eprintln!(
+            "   Warning: input appears clipped ({:.3}% of samples at full scale); transcription quality may suffer",
+            fraction * 100.0
+        );
+    }
+}
+
+fn transcribe_for_strategy(
+    audio: &types::AudioData,
+    settings: &TranscriptionSettings,
+    strategy: types::ChunkStrategy,
+    whisper_dump: Option<&Path>,
+    cancel: &CancelFlag,
+) -> Result<types::Transcript> {
+    match strategy {
+        types::ChunkStrategy::Linguistic | types::ChunkStrategy::Phrase => {
+            transcribe_with_logging(audio, settings, whisper_dump, cancel)
+        }
+        types::ChunkStrategy::Fixed { .. } => {
+            progress!("\n2. Skipping transcription (fixed-duration chunking)");
+            Ok(types::Transcript {
+                segments: Vec::new(),
+            })
+        }
+    }
+}
+
 fn transcribe_with_logging(
     audio: &types::AudioData,
     settings: &TranscriptionSettings,
+    whisper_dump: Option<&Path>,
+    cancel: &CancelFlag,
 ) -> Result<types::Transcript> {
-    println!("\n2. Transcribing audio with Whisper...");
-    let transcript = transcription::transcribe_audio(audio, settings)
+    progress!("\n2. Transcribing audio with Whisper...");
+    let input = transcription::whisper_input(audio, settings.sample_rate);
+    if let Some(path) = whisper_dump {
+        dump_whisper_input(&input, path)?;
+    }
+    let transcript = transcription::transcribe_audio(&input, settings, cancel)
         .context("Failed to transcribe audio")?;
-    println!("   Found {} segments", transcript.segments.len());
+    progress!("   Found {} segments", transcript.segments.len());
     log_transcript_preview(&transcript);
     Ok(transcript)
 }
 
+/// Write the exact buffer handed to Whisper (trimmed, resampled, mono) to `path`
+fn dump_whisper_input(input: &types::AudioData, path: &Path) -> Result<()> {
+    audio::encoder::encode_audio_atomic(input, path, ChannelsOut::Mono, None)
+        .with_context(|| format!("Failed to write Whisper input to {:?}", path))?;
+    progress!("   Wrote Whisper input to {:?}", path);
+    Ok(())
+}
+
 fn log_transcript_preview(transcript: &types::Transcript) {
     let sentence_segments = transcript
         .segments
@@ -254,6 +1236,11 @@ fn log_transcript_preview(transcript: &types::Transcript) {
         .iter()
         .filter(|segment| matches!(segment.granularity, types::Granularity::Word))
         .count();
+    let phrase_segments = transcript
+        .segments
+        .iter()
+        .filter(|segment| matches!(segment.granularity, types::Granularity::Phrase))
+        .count();
     let preview: Vec<String> = transcript
         .segments
         .iter()
@@ -263,14 +1250,17 @@ fn log_transcript_preview(transcript: &types::Transcript) {
         })
         .collect();
     if preview.is_empty() {
-        println!(
-            "   Segment mix: {} sentence / {} word",
-            sentence_segments, word_segments
+        progress!(
+            "   Segment mix: {} sentence / {} phrase / {} word",
+            sentence_segments,
+            phrase_segments,
+            word_segments
         );
     } else {
-        println!(
-            "   Segment mix: {} sentence / {} word; preview: {}",
+        progress!(
+            "   Segment mix: {} sentence / {} phrase / {} word; preview: {}",
             sentence_segments,
+            phrase_segments,
             word_segments,
             preview.join(" | ")
         );
@@ -294,125 +1284,379 @@ fn format_preview_text(text: &str) -> String {
     preview
 }
 
+fn plan_for_strategy(
+    audio: &types::AudioData,
+    transcript: &types::Transcript,
+    strategy: types::ChunkStrategy,
+    args: &Args,
+) -> Result<Vec<types::ChunkBoundary>> {
+    match strategy {
+        types::ChunkStrategy::Linguistic => plan_chunks(
+            audio,
+            transcript,
+            args.target_duration,
+            args.fallback_fixed_chunks,
+            args.speech_band_vad,
+            args.silence_threshold(),
+        ),
+        types::ChunkStrategy::Fixed { duration } => {
+            progress!("\n3. Calculating fixed-duration chunk boundaries...");
+            Ok(plan_fixed_chunks(audio, duration))
+        }
+        types::ChunkStrategy::Phrase => {
+            progress!("\n3. Calculating phrase chunk boundaries...");
+            plan_phrase_chunks(audio, transcript, args)
+        }
+    }
+}
+
 fn plan_chunks(
     audio: &types::AudioData,
     transcript: &types::Transcript,
     target_duration: f64,
-) -> Vec<types::ChunkBoundary> {
-    println!("\n3. Calculating linguistic chunk boundaries...");
+    fallback_fixed: bool,
+    speech_band: bool,
+    silence_threshold: f32,
+) -> Result<Vec<types::ChunkBoundary>> {
+    if transcript.segments.is_empty() {
+        return plan_fallback_chunks(audio, target_duration, fallback_fixed);
+    }
+    progress!("\n3. Calculating linguistic chunk boundaries...");
     let config = types::ChunkConfig::new(target_duration);
-    let pauses = detect_pauses_for_chunking(audio, target_duration);
+    let pauses = detect_pauses_for_chunking(audio, target_duration, speech_band, silence_threshold);
     let pause_count = pauses.len();
-    println!(
+    progress!(
         "   Pause detector: {} candidate pause{}",
         pause_count,
         if pause_count == 1 { "" } else { "s" }
     );
     let boundaries = chunking::calculate_chunk_boundaries(transcript, config, &pauses);
-    println!("   Created {} chunks at natural breaks", boundaries.len());
+    progress!("   Created {} chunks at natural breaks", boundaries.len());
     if !boundaries.is_empty() {
         let total_segments: usize = boundaries
             .iter()
             .map(|boundary| boundary.source_segment_ids.len())
             .sum();
-        println!(
+        progress!(
             "   Average transcript segments per chunk: {:.2}",
             total_segments as f64 / boundaries.len() as f64
         );
     }
-    boundaries
+    Ok(boundaries)
 }
 
-fn detect_pauses_for_chunking(audio: &types::AudioData, target_duration: f64) -> Vec<f64> {
-    let min_silence_duration = (target_duration * 0.2).clamp(0.15, 0.6);
-    let window_duration = 0.05;
-    let silence_threshold = 0.04;
-    audio::pause_detector::detect_pauses(
-        audio,
-        min_silence_duration,
-        silence_threshold,
-        window_duration,
-    )
-}
-fn slice_chunks(
+fn plan_fallback_chunks(
     audio: &types::AudioData,
-    boundaries: &[types::ChunkBoundary],
-) -> Vec<types::AudioChunk> {
-    println!("\n4. Slicing audio into chunks...");
-    let chunks = audio::slicer::slice_audio(audio, boundaries);
-    println!("   Sliced into {} audio chunks", chunks.len());
-    chunks
+    target_duration: f64,
+    fallback_fixed: bool,
+) -> Result<Vec<types::ChunkBoundary>> {
+    ensure!(
+        fallback_fixed,
+        "Transcription produced no segments, so there are no linguistic boundaries to chunk on. \
+         The input may be silent or music-only; try narrowing --start/--end to a spoken section, \
+         setting --whisper-language explicitly, or pass --fallback-fixed-chunks to slice every \
+         {:.1}s regardless of speech",
+        target_duration
+    );
+    progress!("\n3. No transcript segments; falling back to fixed-duration chunks...");
+    Ok(plan_fixed_chunks(audio, target_duration))
 }
 
-fn write_chunks(
-    chunks: &[types::AudioChunk],
-    boundaries: &[types::ChunkBoundary],
-    recipe: &types::Recipe,
-    output_dir: &Path,
-) -> Result<()> {
-    println!("\n5. Applying recipe to each chunk and writing outputs...");
-    fs::create_dir_all(output_dir)
-        .with_context(|| format!("Failed to create output directory {:?}", output_dir))?;
-    let mut written = 0usize;
-    for (index, chunk) in chunks.iter().enumerate() {
-        if write_single_chunk(index, chunk, &boundaries[index], recipe, output_dir)? {
-            written += 1;
-        }
-        log_chunk_progress(index, chunks.len());
+fn plan_phrase_chunks(
+    audio: &types::AudioData,
+    transcript: &types::Transcript,
+    args: &Args,
+) -> Result<Vec<types::ChunkBoundary>> {
+    if transcript.segments.is_empty() {
+        return plan_fallback_chunks(audio, args.target_duration, args.fallback_fixed_chunks);
     }
-    log_chunk_summary(written, output_dir);
-    Ok(())
+    let boundaries = chunking::phrase_chunk_boundaries(transcript);
+    progress!("   Created {} phrase chunks", boundaries.len());
+    Ok(boundaries)
 }
 
-fn write_single_chunk(
+fn plan_fixed_chunks(audio: &types::AudioData, duration: f64) -> Vec<types::ChunkBoundary> {
+    let boundaries = chunking::fixed_chunk_boundaries(audio.duration(), duration);
+    progress!(
+        "   Created {} fixed {:.1}s chunks",
+        boundaries.len(),
+        duration
+    );
+    boundaries
+}
+
+fn overlap_chunks(
+    boundaries: &[types::ChunkBoundary],
+    overlap: f64,
+    transcript: &types::Transcript,
+) -> Vec<types::ChunkBoundary> {
+    if overlap <= 0.0 {
+        return boundaries.to_vec();
+    }
+    progress!(
+        "   Extending each chunk {:.3}s back into its predecessor",
+        overlap
+    );
+    chunking::apply_overlap(boundaries, overlap, transcript)
+}
+
+fn context_tail_chunks(
+    boundaries: &[types::ChunkBoundary],
+    words: usize,
+    transcript: &types::Transcript,
+) -> Vec<types::ChunkBoundary> {
+    if words == 0 {
+        return boundaries.to_vec();
+    }
+    progress!(
+        "   Extending each chunk through up to {} following word segment(s)",
+        words
+    );
+    chunking::apply_context_tail(boundaries, words, transcript)
+}
+
+fn detect_pauses_for_chunking(
+    audio: &types::AudioData,
+    target_duration: f64,
+    speech_band: bool,
+    silence_threshold: f32,
+) -> Vec<f64> {
+    let filtered = speech_band.then(|| audio::filter::speech_band_pass(audio));
+    let audio = filtered.as_ref().unwrap_or(audio);
+    let min_silence_ms = (target_duration * 0.2).clamp(0.15, 0.6) * 1000.0;
+    let window_ms = 50.0;
+    audio::pause_detector::detect_pauses(audio, window_ms, min_silence_ms, silence_threshold)
+}
+/// Slice chunks lazily, timed against the input by shifting them `source_offset` seconds
+fn slice_chunks<'a>(
+    audio: &'a types::AudioData,
+    boundaries: &'a [types::ChunkBoundary],
+    source_offset: f64,
+) -> impl Iterator<Item = types::AudioChunk> + 'a {
+    progress!("\n4. Slicing audio into chunks...");
+    progress!(
+        "   Streaming {} audio chunks to the writer",
+        boundaries.len()
+    );
+    audio::slicer::slice_audio_iter(audio, boundaries).map(move |mut chunk| {
+        chunk.start_time += source_offset;
+        chunk.end_time += source_offset;
+        chunk
+    })
+}
+
+/// Apply the recipe to each chunk and write it out, returning a manifest entry per written chunk
+fn write_chunks(
+    chunks: impl Iterator<Item = types::AudioChunk>,
+    boundaries: &[types::ChunkBoundary],
+    transcript: &types::Transcript,
+    recipe: &types::Recipe,
+    settings: &OutputSettings,
+    events: &mut EventLog<impl std::io::Write>,
+    cancel: &CancelFlag,
+) -> Result<Vec<manifest::ChunkEntry>> {
+    progress!("\n5. Applying recipe to each chunk and writing outputs...");
+    let source_dir = settings.source_dir();
+    let ranges = if settings.gapless_source_ranges {
+        chunking::close_gaps(boundaries)
+    } else {
+        boundaries.to_vec()
+    };
+    let pauses = chunking::natural_pauses(boundaries, transcript);
+    let mut written = Vec::new();
+    for (index, (chunk, boundary)) in chunks.zip(boundaries).enumerate() {
+        cancel::check(cancel)?;
+        let chunk_dir = settings.chunk_dir(index + 1, boundaries.len());
+        let rendered = render_chunk(index, &chunk, pauses[index], recipe, settings)?;
+        if let Some(path) = write_single_chunk(
+            index, rendered, recipe.pan, boundary, transcript, &chunk_dir, settings,
+        )? {
+            events.emit(
+                Event::stage("chunk_written")
+                    .with_chunk(index + 1)
+                    .with_path(&path),
+            )?;
+            written.push(manifest::ChunkEntry {
+                path: path
+                    .strip_prefix(&source_dir)
+                    .unwrap_or(&path)
+                    .to_path_buf(),
+                text: chunk_text(boundary, transcript),
+                source_start: ranges[index].start_time + settings.source_offset,
+                source_end: ranges[index].end_time + settings.source_offset,
+            });
+        }
+        log_chunk_progress(index, boundaries.len());
+    }
+    Ok(written)
+}
+
+/// Apply the recipe to each chunk and append it to one track, written once at the end
+fn write_single_track(
+    chunks: impl Iterator<Item = types::AudioChunk>,
+    boundaries: &[types::ChunkBoundary],
+    transcript: &types::Transcript,
+    recipe: &types::Recipe,
+    settings: &OutputSettings,
+    cancel: &CancelFlag,
+) -> Result<()> {
+    let Some((path, gap_seconds)) = &settings.single_file else {
+        bail!("Single-file output path not set");
+    };
+    progress!(
+        "\n5. Applying recipe to each chunk and joining into {:?}...",
+        path
+    );
+    let mut track = single_file::SingleTrack::new(*gap_seconds, recipe.silence_floor);
+    let pauses = chunking::natural_pauses(boundaries, transcript);
+    for (index, (chunk, boundary)) in chunks.zip(boundaries).enumerate() {
+        cancel::check(cancel)?;
+        if let Some(processed) = render_chunk(index, &chunk, pauses[index], recipe, settings)? {
+            track.push(&processed, chunk_text(boundary, transcript))?;
+        }
+        log_chunk_progress(index, boundaries.len());
+    }
+    let (audio, cues) = track
+        .finish()
+        .ok_or_else(|| anyhow!("No chunks produced audio for {:?}", path))?;
+    let total_seconds = audio.samples.len() as f64 / audio.sample_rate as f64;
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create output directory {:?}", parent))?;
+    }
+    audio::encoder::encode_audio_atomic(
+        &audio,
+        path,
+        settings.channels_out,
+        settings.source_tags.as_ref(),
+    )
+    .with_context(|| format!("Failed to encode single-file output {:?}", path))?;
+    let cue_path = path.with_extension("ffmetadata");
+    single_file::write_cue_sheet(&cues, total_seconds, &cue_path)?;
+    progress!(
+        "   Wrote {} chunks ({:.1}s) to {:?}, chapters in {:?}",
+        cues.len(),
+        total_seconds,
+        path,
+        cue_path
+    );
+    Ok(())
+}
+
+/// Apply the recipe to one chunk, join the pieces and convert to the output rate,
+/// or `None` if the recipe produced nothing
+fn render_chunk(
     index: usize,
     chunk: &types::AudioChunk,
-    boundary: &types::ChunkBoundary,
+    natural_pause: Option<f64>,
     recipe: &types::Recipe,
-    output_dir: &Path,
-) -> Result<bool> {
-    let processed = operations::recipe::apply_recipe(chunk, recipe);
+    settings: &OutputSettings,
+) -> Result<Option<types::AudioData>> {
+    let processed = operations::recipe::apply_recipe(chunk, recipe, natural_pause);
     if processed.is_empty() {
         eprintln!(
             "   Chunk {} produced no processed segments; skipping",
             index + 1
         );
-        return Ok(false);
+        return Ok(None);
     }
-    let chunk_dir = output_dir.join(format!("chunk_{:04}", index + 1));
-    fs::create_dir_all(&chunk_dir)
+    let assembled = audio::assembler::assemble_audio(
+        &processed,
+        settings.crossfade_ms,
+        settings.butt_join_repeats,
+    )
+    .ok_or_else(|| anyhow!("Failed to assemble processed audio for chunk {}", index + 1))?;
+    Ok(Some(match settings.output_rate {
+        Some(rate) => audio::resample::resample(&assembled, rate),
+        None => assembled,
+    }))
+}
+
+fn write_single_chunk(
+    index: usize,
+    rendered: Option<types::AudioData>,
+    pan: Option<types::PanSweep>,
+    boundary: &types::ChunkBoundary,
+    transcript: &types::Transcript,
+    chunk_dir: &Path,
+    settings: &OutputSettings,
+) -> Result<Option<PathBuf>> {
+    let Some(processed_audio) = rendered else {
+        return Ok(None);
+    };
+    fs::create_dir_all(chunk_dir)
         .with_context(|| format!("Failed to create chunk output directory {:?}", chunk_dir))?;
-    let processed_audio = audio::assembler::assemble_audio(&processed)
-        .ok_or_else(|| anyhow!("Failed to assemble processed audio for chunk {}", index + 1))?;
     let output_path = chunk_dir.join("processed.wav");
-    audio::encoder::encode_audio(&processed_audio, &output_path).with_context(|| {
+    let tags = settings
+        .source_tags
+        .as_ref()
+        .map(|tags| tags.for_chunk(&settings.stem, index + 1));
+    match pan {
+        Some(sweep) => audio::encoder::encode_stereo_atomic(
+            &operations::pan::pan_chunk(&processed_audio, sweep.start, sweep.end),
+            &output_path,
+            tags.as_ref(),
+        ),
+        None => audio::encoder::encode_audio_atomic(
+            &processed_audio,
+            &output_path,
+            settings.channels_out,
+            tags.as_ref(),
+        ),
+    }
+    .with_context(|| {
         format!(
             "Failed to encode processed audio for chunk {} at {:?}",
             index + 1,
             output_path
         )
     })?;
-    println!(
+    if let Some(buckets) = settings.envelope_buckets {
+        audio::envelope::write_envelope(
+            &processed_audio,
+            buckets,
+            &chunk_dir.join("envelope.json"),
+        )?;
+    }
+    if settings.chunk_text {
+        let text_path = chunk_dir.join("transcript.txt");
+        fs::write(&text_path, chunk_text(boundary, transcript))
+            .with_context(|| format!("Failed to write chunk text {:?}", text_path))?;
+    }
+    progress!(
         "   Wrote chunk {:04} to {:?} ({:.3}s → {:.3}s, {} transcript segments)",
         index + 1,
         output_path,
-        chunk.start_time,
-        chunk.end_time,
+        boundary.start_time + settings.source_offset,
+        boundary.end_time + settings.source_offset,
         boundary.source_segment_ids.len()
     );
-    Ok(true)
+    Ok(Some(output_path))
+}
+
+/// Transcript text of the segments that make up a chunk, joined with spaces
+fn chunk_text(boundary: &types::ChunkBoundary, transcript: &types::Transcript) -> String {
+    boundary
+        .source_segment_ids
+        .iter()
+        .filter_map(|&id| transcript.segments.get(id))
+        .map(|segment| segment.text.trim())
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
 }
 
 fn log_chunk_progress(index: usize, total: usize) {
     if (index + 1).is_multiple_of(10) || index + 1 == total {
-        println!("   Processed {}/{} chunks", index + 1, total);
+        progress!("   Processed {}/{} chunks", index + 1, total);
     }
 }
 
 fn log_chunk_summary(written: usize, output_dir: &Path) {
-    println!(
+    progress!(
         "   Completed writing {} chunk files under {:?}",
-        written, output_dir
+        written,
+        output_dir
     );
 }
 
@@ -545,11 +1789,9 @@ mod tests {
         assert_eq!(recipe.steps.len(), 1);
     }
 
-    #[test]
-    fn test_verify_cli_args_compile() {
-        // This test just ensures Args can be constructed
-        let args = Args {
-            input_file: PathBuf::from("test.wav"),
+    fn base_args() -> Args {
+        Args {
+            input_files: vec![PathBuf::from("test.wav")],
             output_dir: PathBuf::from("output"),
             target_duration: 2.0,
             recipe_json: Some("{}".to_string()),
@@ -558,24 +1800,69 @@ mod tests {
             end: None,
             whisper_model: None,
             whisper_language: None,
-        };
+            whisper_sample_rate: None,
+            whisper_threads: None,
+            whisper_prompt: None,
+            whisper_temperature: None,
+            whisper_no_speech_threshold: None,
+            whisper_entropy_threshold: None,
+            whisper_merge_overlaps: false,
+            whisper_beam_size: None,
+            whisper_best_of: None,
+            whisper_patience: None,
+            phrase_min_seconds: 0.6,
+            phrase_max_seconds: 2.0,
+            clip_warn_fraction: 0.001,
+            max_duration_minutes: None,
+            strict: false,
+            downmix: DownmixMode::Average,
+            fixed_duration: None,
+            phrase_chunks: false,
+            overlap: 0.0,
+            context_tail_words: 0,
+            trim_silence_db: None,
+            speech_band_vad: false,
+            fallback_fixed_chunks: false,
+            dump_intermediate: None,
+            keep_intermediate_wav: None,
+            from_intermediate: None,
+            channels_out: ChannelsOut::Same,
+            gapless_source_ranges: false,
+            output_rate: None,
+            copy_source_tags: false,
+            max_parallel_files: 1,
+            resume: false,
+            force: false,
+            anki_csv: None,
+            speed_algo: types::SpeedAlgorithm::Signalsmith,
+            natural_pause_scale: None,
+            mix_reference: None,
+            mix_gain_db: -18.0,
+            crossfade_ms: 2.0,
+            butt_join_repeats: false,
+            single_file: None,
+            single_file_gap_ms: 1000.0,
+            dry_run: false,
+            chunk_text: false,
+            pad_width: None,
+            envelopes: false,
+            envelope_buckets: 256,
+            quiet: false,
+            json_logs: false,
+        }
+    }
+
+    #[test]
+    fn test_verify_cli_args_compile() {
+        // This test just ensures Args can be constructed
+        let args = base_args();
 
         assert_eq!(args.target_duration, 2.0);
     }
 
     #[test]
     fn transcription_settings_defaults_enable_detection() {
-        let args = Args {
-            input_file: PathBuf::from("test.wav"),
-            output_dir: PathBuf::from("output"),
-            target_duration: 2.0,
-            recipe_json: Some("{}".to_string()),
-            recipe_file: None,
-            start: None,
-            end: None,
-            whisper_model: None,
-            whisper_language: None,
-        };
+        let args = base_args();
 
         let settings = args.transcription_settings().unwrap();
         assert!(settings.detect_language);
@@ -585,15 +1872,9 @@ mod tests {
     #[test]
     fn transcription_settings_with_language_disables_detection() {
         let args = Args {
-            input_file: PathBuf::from("test.wav"),
-            output_dir: PathBuf::from("output"),
-            target_duration: 2.0,
-            recipe_json: Some("{}".to_string()),
-            recipe_file: None,
-            start: None,
-            end: None,
             whisper_model: Some(PathBuf::from("/tmp/whisper.bin")),
             whisper_language: Some("es".to_string()),
+            ..base_args()
         };
 
         let settings = args.transcription_settings().unwrap();
@@ -605,15 +1886,8 @@ mod tests {
     #[test]
     fn transcription_settings_force_english_for_english_only_models() {
         let args = Args {
-            input_file: PathBuf::from("test.wav"),
-            output_dir: PathBuf::from("output"),
-            target_duration: 2.0,
-            recipe_json: Some("{}".to_string()),
-            recipe_file: None,
-            start: None,
-            end: None,
             whisper_model: Some(PathBuf::from("/tmp/ggml-base.en.bin")),
-            whisper_language: None,
+            ..base_args()
         };
 
         let settings = args.transcription_settings().unwrap();
@@ -622,6 +1896,941 @@ mod tests {
         assert!(!settings.detect_language);
     }
 
+    #[test]
+    fn transcription_settings_pick_decoding_by_model_size() {
+        let small = Args {
+            whisper_model: Some(PathBuf::from("/tmp/ggml-small.bin")),
+            ..base_args()
+        };
+        assert_eq!(
+            small.transcription_settings().unwrap().decoding(),
+            transcription::Decoding::Greedy { best_of: 5 }
+        );
+
+        let tiny = Args {
+            whisper_model: Some(PathBuf::from("/tmp/ggml-tiny.en.bin")),
+            ..base_args()
+        };
+        assert_eq!(
+            tiny.transcription_settings().unwrap().decoding(),
+            transcription::Decoding::BeamSearch {
+                beam_size: 5,
+                patience: 1.0
+            }
+        );
+
+        let explicit = Args {
+            whisper_beam_size: Some(2),
+            ..small
+        };
+        assert_eq!(
+            explicit.transcription_settings().unwrap().decoding(),
+            transcription::Decoding::BeamSearch {
+                beam_size: 2,
+                patience: 1.0
+            }
+        );
+    }
+
+    #[test]
+    fn transcription_settings_only_accept_whisper_rate() {
+        let settings = base_args().transcription_settings().unwrap();
+        assert_eq!(settings.sample_rate, 16_000);
+
+        let rate = |hz: u32| {
+            Args {
+                whisper_sample_rate: Some(hz),
+                ..base_args()
+            }
+            .transcription_settings()
+        };
+        assert_eq!(rate(16_000).unwrap().sample_rate, 16_000);
+        assert!(rate(8_000).is_err());
+        assert!(rate(44_100).is_err());
+    }
+
+    #[test]
+    fn whisper_dump_holds_resampled_input() {
+        let path = std::env::temp_dir().join(format!(
+            "flowalyzer_whisper_input_{}.wav",
+            std::process::id()
+        ));
+        let audio = types::AudioData {
+            samples: vec![0.25; 22_050],
+            sample_rate: 44_100,
+        };
+
+        let input = transcription::whisper_input(&audio, 16_000);
+        dump_whisper_input(&input, &path).unwrap();
+        let reader = hound::WavReader::open(&path).unwrap();
+        let (spec, frames) = (reader.spec(), reader.duration());
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(spec.sample_rate, 16_000);
+        assert_eq!(spec.channels, 1);
+        assert_eq!(frames as usize, input.samples.len());
+    }
+
+    #[test]
+    fn empty_transcript_without_fallback_errors() {
+        let audio = types::AudioData {
+            samples: vec![0.0; 6_000],
+            sample_rate: 1_000,
+        };
+        let transcript = types::Transcript {
+            segments: Vec::new(),
+        };
+        assert!(plan_chunks(
+            &audio,
+            &transcript,
+            2.0,
+            false,
+            false,
+            audio::pause_detector::DEFAULT_SILENCE_THRESHOLD
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn empty_transcript_with_fallback_uses_fixed_chunks() {
+        let audio = types::AudioData {
+            samples: vec![0.0; 6_000],
+            sample_rate: 1_000,
+        };
+        let transcript = types::Transcript {
+            segments: Vec::new(),
+        };
+        let boundaries = plan_chunks(
+            &audio,
+            &transcript,
+            2.0,
+            true,
+            false,
+            audio::pause_detector::DEFAULT_SILENCE_THRESHOLD,
+        )
+        .unwrap();
+        let starts: Vec<f64> = boundaries.iter().map(|b| b.start_time).collect();
+        assert_eq!(starts, vec![0.0, 2.0, 4.0]);
+        assert!(boundaries
+            .iter()
+            .all(|b| (b.end_time - b.start_time - 2.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn fixed_duration_selects_fixed_strategy() {
+        assert_eq!(
+            base_args().chunk_strategy(),
+            types::ChunkStrategy::Linguistic
+        );
+        let args = Args {
+            fixed_duration: Some(3.0),
+            ..base_args()
+        };
+        assert_eq!(
+            args.chunk_strategy(),
+            types::ChunkStrategy::Fixed { duration: 3.0 }
+        );
+        let phrase = Args {
+            phrase_chunks: true,
+            ..base_args()
+        };
+        assert_eq!(phrase.chunk_strategy(), types::ChunkStrategy::Phrase);
+    }
+
+    fn edges(boundaries: &[types::ChunkBoundary]) -> Vec<f64> {
+        boundaries.iter().skip(1).map(|b| b.start_time).collect()
+    }
+
+    fn one_segment(end_time: f64) -> types::Transcript {
+        types::Transcript {
+            segments: vec![types::Segment {
+                text: "long sentence".to_string(),
+                start_time: 0.0,
+                end_time,
+                granularity: types::Granularity::Sentence,
+            }],
+        }
+    }
+
+    #[test]
+    fn plan_chunks_splits_only_at_pauses_longer_than_the_minimum() {
+        // 60 ms dip between words at 2.1s, a 500 ms pause at 3.0-3.5s
+        let mut samples = vec![0.5; 6_000];
+        samples[2_070..2_130].fill(0.0);
+        samples[3_000..3_500].fill(0.0);
+        let audio = types::AudioData {
+            samples,
+            sample_rate: 1_000,
+        };
+
+        let boundaries = plan_chunks(
+            &audio,
+            &one_segment(6.0),
+            2.0,
+            false,
+            false,
+            audio::pause_detector::DEFAULT_SILENCE_THRESHOLD,
+        )
+        .unwrap();
+        let edges = edges(&boundaries);
+        assert!(edges.iter().any(|t| (t - 3.25).abs() < 0.05), "{:?}", edges);
+        assert!(!edges.iter().any(|t| (t - 2.1).abs() < 0.2), "{:?}", edges);
+    }
+
+    #[test]
+    fn plan_chunks_finds_pauses_under_rumble_with_speech_band() {
+        let sample_rate = 16_000;
+        let wave = |freq: f32, amp: f32, len: usize| {
+            (0..len).map(move |i| {
+                amp * (2.0 * std::f32::consts::PI * freq * i as f32 / sample_rate as f32).sin()
+            })
+        };
+        let audio = types::AudioData {
+            samples: wave(1_000.0, 0.5, 16_000)
+                .chain(wave(50.0, 0.3, 9_600))
+                .chain(wave(1_000.0, 0.5, 16_000))
+                .collect(),
+            sample_rate,
+        };
+        let plan = |speech_band: bool| {
+            plan_chunks(
+                &audio,
+                &one_segment(2.6),
+                1.0,
+                false,
+                speech_band,
+                audio::pause_detector::DEFAULT_SILENCE_THRESHOLD,
+            )
+            .unwrap()
+        };
+
+        // Without the filter the rumble hides the pause, so the span is cut at the target
+        assert_eq!(edges(&plan(false)), vec![1.0, 2.0]);
+        let edges = edges(&plan(true));
+        assert_eq!(edges.len(), 1, "{:?}", edges);
+        assert!((edges[0] - 1.3).abs() < 0.1, "{:?}", edges);
+    }
+
+    #[test]
+    fn resuming_from_intermediate_reproduces_boundaries() {
+        let mut samples = vec![0.6; 3_000];
+        samples[1_200..1_600].fill(0.0);
+        let audio = types::AudioData {
+            samples,
+            sample_rate: 1_000,
+        };
+        let transcript = types::Transcript {
+            segments: vec![
+                types::Segment {
+                    text: "Hola".to_string(),
+                    start_time: 0.0,
+                    end_time: 1.8,
+                    granularity: types::Granularity::Sentence,
+                },
+                types::Segment {
+                    text: "amigo".to_string(),
+                    start_time: 1.8,
+                    end_time: 3.0,
+                    granularity: types::Granularity::Sentence,
+                },
+            ],
+        };
+        let dir =
+            std::env::temp_dir().join(format!("flowalyzer_intermediate_{}", std::process::id()));
+        let source = intermediate::Source {
+            input: PathBuf::from("lesson.mp3"),
+            offset: 12.5,
+        };
+
+        intermediate::dump_intermediate(&dir, &source, &audio, &transcript).unwrap();
+        let (loaded_audio, loaded_transcript) = intermediate::load_intermediate(&dir).unwrap();
+        let mut args = Args {
+            input_files: Vec::new(),
+            from_intermediate: Some(dir.clone()),
+            ..base_args()
+        };
+        args.resolve_intermediate_input().unwrap();
+        let loaded_source = intermediate::read_source(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded_source, source);
+        assert_eq!(args.input_files, vec![source.input]);
+        assert_eq!(loaded_audio.samples, audio.samples);
+        assert_eq!(loaded_audio.sample_rate, audio.sample_rate);
+
+        let original = plan_chunks(
+            &audio,
+            &transcript,
+            1.0,
+            false,
+            false,
+            audio::pause_detector::DEFAULT_SILENCE_THRESHOLD,
+        )
+        .unwrap();
+        let resumed = plan_chunks(
+            &loaded_audio,
+            &loaded_transcript,
+            1.0,
+            false,
+            false,
+            audio::pause_detector::DEFAULT_SILENCE_THRESHOLD,
+        )
+        .unwrap();
+        assert_eq!(original.len(), resumed.len());
+        for (a, b) in original.iter().zip(&resumed) {
+            assert_eq!(a.start_time, b.start_time);
+            assert_eq!(a.end_time, b.end_time);
+            assert_eq!(a.source_segment_ids, b.source_segment_ids);
+        }
+    }
+
+    #[test]
+    fn transcription_settings_take_thread_count_from_args() {
+        let defaults = base_args().transcription_settings().unwrap();
+        assert!(defaults.threads >= 1);
+
+        let args = Args {
+            whisper_threads: Some(6),
+            ..base_args()
+        };
+        assert_eq!(args.transcription_settings().unwrap().threads, 6);
+
+        let zero = Args {
+            whisper_threads: Some(0),
+            ..base_args()
+        };
+        assert!(zero.transcription_settings().is_err());
+    }
+
+    #[test]
+    fn transcription_settings_carry_initial_prompt() {
+        assert!(base_args()
+            .transcription_settings()
+            .unwrap()
+            .initial_prompt
+            .is_none());
+
+        let args = Args {
+            whisper_prompt: Some("  Kubernetes, etcd, kubelet ".to_string()),
+            ..base_args()
+        };
+        let settings = args.transcription_settings().unwrap();
+        assert_eq!(
+            settings.initial_prompt.as_deref(),
+            Some("Kubernetes, etcd, kubelet")
+        );
+    }
+
+    #[test]
+    fn transcription_settings_carry_decoding_thresholds() {
+        let args = Args {
+            whisper_temperature: Some(0.4),
+            whisper_no_speech_threshold: Some(0.5),
+            whisper_entropy_threshold: Some(2.0),
+            ..base_args()
+        };
+        let settings = args.transcription_settings().unwrap();
+        assert_eq!(settings.temperature, Some(0.4));
+        assert_eq!(settings.no_speech_threshold, Some(0.5));
+        assert_eq!(settings.entropy_threshold, Some(2.0));
+
+        let invalid = Args {
+            whisper_no_speech_threshold: Some(1.5),
+            ..base_args()
+        };
+        assert!(invalid.transcription_settings().is_err());
+    }
+
+    #[test]
+    fn output_settings_expand_template_with_input_stem() {
+        let args = Args {
+            output_dir: PathBuf::from("out/{stem}/chunk_{n:03}"),
+            ..base_args()
+        };
+        let settings = args.output_settings(Path::new("audio/lesson.mp3")).unwrap();
+        assert_eq!(
+            settings.chunk_dir(2, 12),
+            PathBuf::from("out/lesson/chunk_002")
+        );
+
+        let invalid = Args {
+            output_dir: PathBuf::from("out/{unknown}"),
+            ..base_args()
+        };
+        assert!(invalid.output_settings(Path::new("test.wav")).is_err());
+    }
+
+    #[test]
+    fn json_logs_report_each_written_chunk() {
+        let output_dir =
+            std::env::temp_dir().join(format!("flowalyzer_json_logs_{}", std::process::id()));
+        let args = Args {
+            output_dir: output_dir.clone(),
+            json_logs: true,
+            ..base_args()
+        };
+        let audio = types::AudioData {
+            samples: vec![0.1; 800],
+            sample_rate: 100,
+        };
+        let boundaries = plan_fixed_chunks(&audio, 4.0);
+        let recipe = types::Recipe::new("once").add_step(types::RecipeStep::audio(1, 1.0));
+        let mut events = EventLog::new(args.log_mode(), Vec::new());
+        write_chunks(
+            slice_chunks(&audio, &boundaries, 0.0),
+            &boundaries,
+            &types::Transcript {
+                segments: Vec::new(),
+            },
+            &recipe,
+            &args.output_settings(Path::new("test.wav")).unwrap(),
+            &mut events,
+            &CancelFlag::default(),
+        )
+        .unwrap();
+        let output = String::from_utf8(events.into_inner().unwrap()).unwrap();
+        let lines: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        for (idx, event) in lines.iter().enumerate() {
+            assert_eq!(event["stage"], "chunk_written");
+            assert_eq!(event["chunk_index"], idx + 1);
+            assert!(event["path"].as_str().unwrap().ends_with("processed.wav"));
+        }
+        fs::remove_dir_all(&output_dir).unwrap();
+    }
+
+    #[test]
+    fn unprobeable_tags_fail_only_their_own_input() {
+        let args = Args {
+            input_files: vec![PathBuf::from("missing.wav"), PathBuf::from("other.wav")],
+            copy_source_tags: true,
+            ..base_args()
+        };
+        let missing = Path::new("missing.wav");
+        // Planning the batch never touches the file; process_file reports it per input
+        assert!(args.output_settings(missing).is_ok());
+        assert!(args.source_tags(missing).is_err());
+    }
+
+    #[test]
+    fn copied_source_tags_reach_each_chunk() {
+        let output_dir =
+            std::env::temp_dir().join(format!("flowalyzer_copy_tags_{}", std::process::id()));
+        fs::create_dir_all(&output_dir).unwrap();
+        let audio = types::AudioData {
+            samples: vec![0.1; 800],
+            sample_rate: 100,
+        };
+        let source = output_dir.join("lesson.wav");
+        let source_tags = AudioTags {
+            title: Some("Lesson 1".into()),
+            artist: Some("Narrator".into()),
+            album: Some("Course One".into()),
+            track: None,
+        };
+        audio::encoder::encode_audio_atomic(&audio, &source, ChannelsOut::Same, Some(&source_tags))
+            .unwrap();
+        let args = Args {
+            output_dir: output_dir.join("chunk_{n}"),
+            copy_source_tags: true,
+            ..base_args()
+        };
+        let boundaries = plan_fixed_chunks(&audio, 4.0);
+        let recipe = types::Recipe::new("once").add_step(types::RecipeStep::audio(1, 1.0));
+        write_chunks(
+            slice_chunks(&audio, &boundaries, 0.0),
+            &boundaries,
+            &types::Transcript {
+                segments: Vec::new(),
+            },
+            &recipe,
+            &OutputSettings {
+                source_tags: args.source_tags(&source).unwrap(),
+                ..args.output_settings(&source).unwrap()
+            },
+            &mut EventLog::new(LogMode::Quiet, Vec::new()),
+            &CancelFlag::default(),
+        )
+        .unwrap();
+        let chunk_tags =
+            audio::tags::read_tags(&output_dir.join("chunk_2").join("processed.wav")).unwrap();
+        fs::remove_dir_all(&output_dir).unwrap();
+
+        assert_eq!(chunk_tags.album.as_deref(), Some("Course One"));
+        assert_eq!(chunk_tags.artist.as_deref(), Some("Narrator"));
+        assert_eq!(chunk_tags.title.as_deref(), Some("Lesson 1 (2)"));
+        assert_eq!(chunk_tags.track.as_deref(), Some("2"));
+    }
+
+    #[test]
+    fn panned_recipe_writes_stereo_chunks_sweeping_left_to_right() {
+        let output_dir =
+            std::env::temp_dir().join(format!("flowalyzer_pan_{}", std::process::id()));
+        let args = Args {
+            output_dir: output_dir.join("chunk_{n}"),
+            ..base_args()
+        };
+        let audio = types::AudioData {
+            samples: vec![0.5; 400],
+            sample_rate: 100,
+        };
+        let boundaries = plan_fixed_chunks(&audio, 4.0);
+        let mut recipe = types::Recipe::new("sweep").add_step(types::RecipeStep::audio(1, 1.0));
+        recipe.pan = Some(types::PanSweep {
+            start: -1.0,
+            end: 1.0,
+        });
+        write_chunks(
+            slice_chunks(&audio, &boundaries, 0.0),
+            &boundaries,
+            &types::Transcript {
+                segments: Vec::new(),
+            },
+            &recipe,
+            &args.output_settings(Path::new("lesson.wav")).unwrap(),
+            &mut EventLog::new(LogMode::Quiet, Vec::new()),
+            &CancelFlag::default(),
+        )
+        .unwrap();
+        let mut reader =
+            hound::WavReader::open(output_dir.join("chunk_1").join("processed.wav")).unwrap();
+        let channels = reader.spec().channels;
+        let samples: Vec<i16> = reader.samples::<i16>().map(Result::unwrap).collect();
+        fs::remove_dir_all(&output_dir).unwrap();
+
+        assert_eq!(channels, 2);
+        assert_eq!(samples.len(), 800);
+        let (first, last) = (&samples[..2], &samples[798..]);
+        assert!(first[0] > first[1], "{:?}", first);
+        assert!(last[1] > last[0], "{:?}", last);
+    }
+
+    #[test]
+    fn panned_recipe_rejects_single_file_and_mono_output() {
+        let runtime: types::RuntimeRecipe = serde_json::from_str(
+            r#"{"steps": [{"repeat_count": 1, "speed_factor": 1.0}],
+                "pan": {"start": -1.0, "end": 1.0}}"#,
+        )
+        .unwrap();
+        let single_file = Args {
+            single_file: Some(PathBuf::from("track.wav")),
+            ..base_args()
+        };
+        let mono = Args {
+            channels_out: ChannelsOut::Mono,
+            ..base_args()
+        };
+
+        assert!(load_recipe(&base_args(), &runtime).is_ok());
+        assert!(load_recipe(&single_file, &runtime).is_err());
+        assert!(load_recipe(&mono, &runtime).is_err());
+    }
+
+    #[test]
+    fn cancelled_run_writes_no_chunks() {
+        let output_dir =
+            std::env::temp_dir().join(format!("flowalyzer_cancel_{}", std::process::id()));
+        let args = Args {
+            output_dir: output_dir.clone(),
+            ..base_args()
+        };
+        let audio = types::AudioData {
+            samples: vec![0.1; 800],
+            sample_rate: 100,
+        };
+        let boundaries = plan_fixed_chunks(&audio, 4.0);
+        let recipe = types::Recipe::new("once").add_step(types::RecipeStep::audio(1, 1.0));
+        let cancel = CancelFlag::default();
+        cancel.store(true, std::sync::atomic::Ordering::Relaxed);
+        let result = write_chunks(
+            slice_chunks(&audio, &boundaries, 0.0),
+            &boundaries,
+            &types::Transcript {
+                segments: Vec::new(),
+            },
+            &recipe,
+            &args.output_settings(Path::new("test.wav")).unwrap(),
+            &mut EventLog::new(LogMode::Quiet, std::io::sink()),
+            &cancel,
+        );
+        assert!(result.unwrap_err().is::<cancel::Cancelled>());
+        assert!(!output_dir.exists());
+    }
+
+    #[test]
+    fn track_bed_follows_the_trim_offset() {
+        // Each track sample holds its own time in seconds
+        let track = types::MixBed::Track {
+            audio: std::sync::Arc::new(types::AudioData {
+                samples: (0..7_000).map(|i| i as f32 / 100.0).collect(),
+                sample_rate: 100,
+            }),
+        };
+        let trimmed = types::AudioData {
+            samples: vec![0.1; 800],
+            sample_rate: 100,
+        };
+        let boundaries = plan_fixed_chunks(&trimmed, 4.0);
+        let starts: Vec<f32> = slice_chunks(&trimmed, &boundaries, 60.0)
+            .map(|chunk| operations::bed_samples(&chunk, &track)[0])
+            .collect();
+        assert_eq!(starts, vec![60.0, 64.0]);
+    }
+
+    #[test]
+    fn manifest_ranges_point_into_the_untrimmed_input() {
+        let output_dir =
+            std::env::temp_dir().join(format!("flowalyzer_offset_{}", std::process::id()));
+        let args = Args {
+            output_dir: output_dir.clone(),
+            ..base_args()
+        };
+        let audio = types::AudioData {
+            samples: vec![0.1; 800],
+            sample_rate: 100,
+        };
+        let boundaries = plan_fixed_chunks(&audio, 4.0);
+        let recipe = types::Recipe::new("once").add_step(types::RecipeStep::audio(1, 1.0));
+        let settings = OutputSettings {
+            source_offset: 60.0,
+            ..args.output_settings(Path::new("test.wav")).unwrap()
+        };
+        let chunks = write_chunks(
+            slice_chunks(&audio, &boundaries, settings.source_offset),
+            &boundaries,
+            &types::Transcript {
+                segments: Vec::new(),
+            },
+            &recipe,
+            &settings,
+            &mut EventLog::new(LogMode::Quiet, std::io::sink()),
+            &CancelFlag::default(),
+        )
+        .unwrap();
+        fs::remove_dir_all(&output_dir).unwrap();
+
+        let ranges: Vec<(f64, f64)> = chunks
+            .iter()
+            .map(|chunk| (chunk.source_start, chunk.source_end))
+            .collect();
+        assert_eq!(ranges, vec![(60.0, 64.0), (64.0, 68.0)]);
+    }
+
+    #[test]
+    fn output_rate_resamples_rendered_chunks() {
+        let args = Args {
+            output_rate: Some(48_000),
+            ..base_args()
+        };
+        let chunk = types::AudioChunk {
+            samples: vec![0.1; 44_100],
+            sample_rate: 44_100,
+            start_time: 0.0,
+            end_time: 1.0,
+        };
+        let recipe = types::Recipe::new("once").add_step(types::RecipeStep::audio(1, 1.0));
+        let settings = args.output_settings(Path::new("test.wav")).unwrap();
+
+        let rendered = render_chunk(0, &chunk, None, &recipe, &settings)
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(rendered.sample_rate, 48_000);
+        assert_eq!(rendered.samples.len(), 48_000);
+    }
+
+    #[test]
+    fn chunk_text_joins_segments_into_transcript_file() {
+        let output_dir =
+            std::env::temp_dir().join(format!("flowalyzer_chunk_text_{}", std::process::id()));
+        let args = Args {
+            output_dir: output_dir.clone(),
+            chunk_text: true,
+            ..base_args()
+        };
+        let segment = |text: &str, start_time: f64, end_time: f64| types::Segment {
+            text: text.to_string(),
+            start_time,
+            end_time,
+            granularity: types::Granularity::Sentence,
+        };
+        let transcript = types::Transcript {
+            segments: vec![segment(" Hola,", 0.0, 1.0), segment(" ¿qué tal?", 1.0, 2.0)],
+        };
+        let audio = types::AudioData {
+            samples: vec![0.1; 200],
+            sample_rate: 100,
+        };
+        let boundaries = vec![types::ChunkBoundary {
+            start_time: 0.0,
+            end_time: 2.0,
+            source_segment_ids: vec![0, 1],
+        }];
+        let recipe = types::Recipe::new("once").add_step(types::RecipeStep::audio(1, 1.0));
+        write_chunks(
+            slice_chunks(&audio, &boundaries, 0.0),
+            &boundaries,
+            &transcript,
+            &recipe,
+            &args.output_settings(Path::new("test.wav")).unwrap(),
+            &mut EventLog::new(LogMode::Quiet, std::io::sink()),
+            &CancelFlag::default(),
+        )
+        .unwrap();
+        let text = fs::read_to_string(output_dir.join("chunk_1/transcript.txt")).unwrap();
+        fs::remove_dir_all(&output_dir).unwrap();
+        assert_eq!(text, "Hola, ¿qué tal?");
+    }
+
+    #[test]
+    fn trim_silence_db_rejects_nan_and_levels_above_full_scale() {
+        let input =
+            std::env::temp_dir().join(format!("flowalyzer_trim_db_{}.wav", std::process::id()));
+        fs::write(&input, b"").unwrap();
+        let validate = |db: f64| {
+            Args {
+                input_files: vec![input.clone()],
+                trim_silence_db: Some(db),
+                ..base_args()
+            }
+            .validate()
+        };
+        let results = (validate(-45.0), validate(3.0), validate(f64::NAN));
+        fs::remove_file(&input).unwrap();
+
+        assert!(results.0.is_ok(), "{:?}", results.0);
+        assert!(results.1.is_err());
+        assert!(results.2.is_err());
+    }
+
+    #[test]
+    fn from_intermediate_takes_no_input_or_trim() {
+        let args = Cli::try_parse_from([
+            "flowalyzer",
+            "--from-intermediate",
+            "dump",
+            "out",
+            "--recipe-json",
+            "{}",
+        ])
+        .unwrap()
+        .args
+        .unwrap();
+        assert!(args.input_files.is_empty());
+        assert_eq!(args.output_dir, PathBuf::from("out"));
+
+        let dir = std::env::temp_dir().join(format!(
+            "flowalyzer_intermediate_args_{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let resume = Args {
+            input_files: Vec::new(),
+            from_intermediate: Some(dir.clone()),
+            ..base_args()
+        };
+        let with_input = Args {
+            input_files: vec![PathBuf::from("test.wav")],
+            ..resume.clone()
+        };
+        let with_trim = Args {
+            start: Some("1.0".to_string()),
+            ..resume.clone()
+        };
+        let results = (
+            resume.validate(),
+            with_input.validate(),
+            with_trim.validate(),
+        );
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(results.0.is_ok(), "{:?}", results.0);
+        assert!(results.1.is_err());
+        assert!(results.2.is_err());
+    }
+
+    #[test]
+    fn compare_is_a_subcommand_beside_input_processing() {
+        let compare = Cli::try_parse_from(["flowalyzer", "compare", "deck_a", "deck_b"]).unwrap();
+        assert!(compare.args.is_none());
+        let Some(Command::Compare(decks)) = compare.command else {
+            panic!("expected the compare subcommand");
+        };
+        assert_eq!(decks.dir_a, PathBuf::from("deck_a"));
+        assert_eq!(decks.dir_b, PathBuf::from("deck_b"));
+
+        let process = Cli::try_parse_from(["flowalyzer", "./compare", "out"]).unwrap();
+        assert!(process.command.is_none());
+        let args = process.args.unwrap();
+        assert_eq!(args.input_files, vec![PathBuf::from("./compare")]);
+        assert_eq!(args.output_dir, PathBuf::from("out"));
+
+        let help = Cli::try_parse_from(["flowalyzer", "--help"])
+            .unwrap_err()
+            .to_string();
+        assert!(help.contains("compare"), "{}", help);
+    }
+
+    #[test]
+    fn batch_inputs_parse_and_nest_under_stem() {
+        let args = Cli::try_parse_from([
+            "flowalyzer",
+            "one.wav",
+            "two.mp3",
+            "out",
+            "--recipe-json",
+            "{}",
+            "--max-parallel-files",
+            "2",
+        ])
+        .unwrap()
+        .args
+        .unwrap();
+        assert_eq!(
+            args.input_files,
+            vec![PathBuf::from("one.wav"), PathBuf::from("two.mp3")]
+        );
+        assert_eq!(args.output_dir, PathBuf::from("out"));
+        assert_eq!(args.max_parallel_files, 2);
+
+        let settings = args.output_settings(Path::new("two.mp3")).unwrap();
+        assert_eq!(settings.chunk_dir(1, 3), PathBuf::from("out/two/chunk_1"));
+    }
+
+    #[test]
+    fn resume_skips_only_matching_manifests() {
+        let root = std::env::temp_dir().join(format!("flowalyzer_resume_{}", std::process::id()));
+        let args = Args {
+            input_files: vec![PathBuf::from("done.wav"), PathBuf::from("stale.wav")],
+            output_dir: root.clone(),
+            resume: true,
+            ..base_args()
+        };
+        let recipe = args.runtime_recipe().unwrap();
+        let hash = args.settings_hash(&recipe).unwrap();
+        let changed = Args {
+            target_duration: 3.0,
+            ..args.clone()
+        };
+        let stale_hash = changed.settings_hash(&recipe).unwrap();
+        assert_ne!(hash, stale_hash);
+
+        let (done_wav, stale_wav) = (Path::new("done.wav"), Path::new("stale.wav"));
+        let done = args.output_settings(done_wav).unwrap();
+        let stale = args.output_settings(stale_wav).unwrap();
+        write_file_manifest(done_wav, &done, &hash, Vec::new()).unwrap();
+        write_file_manifest(stale_wav, &stale, &stale_hash, Vec::new()).unwrap();
+
+        assert!(args.skip_processed(done_wav, &done, &hash));
+        assert!(!args.skip_processed(stale_wav, &stale, &hash));
+        let forced = Args {
+            force: true,
+            ..args.clone()
+        };
+        assert!(!forced.skip_processed(done_wav, &done, &hash));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn resume_keeps_a_manifest_per_input_in_shared_folders() {
+        let root =
+            std::env::temp_dir().join(format!("flowalyzer_resume_shared_{}", std::process::id()));
+        let args = Args {
+            input_files: vec![PathBuf::from("a.wav"), PathBuf::from("b.wav")],
+            output_dir: root.join("{stem}-{n}"),
+            resume: true,
+            ..base_args()
+        };
+        let hash = args.settings_hash(&args.runtime_recipe().unwrap()).unwrap();
+        let (a_wav, b_wav) = (Path::new("a.wav"), Path::new("b.wav"));
+        let a = args.output_settings(a_wav).unwrap();
+        let b = args.output_settings(b_wav).unwrap();
+        assert_eq!(a.source_dir(), b.source_dir());
+        assert_ne!(a.manifest_path(), b.manifest_path());
+
+        write_file_manifest(a_wav, &a, &hash, Vec::new()).unwrap();
+        assert!(args.skip_processed(a_wav, &a, &hash));
+        assert!(!args.skip_processed(b_wav, &b, &hash));
+        // A manifest only vouches for the input it was written for
+        assert!(!args.skip_processed(Path::new("elsewhere/a.wav"), &a, &hash));
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn settings_hash_ignores_batch_and_output_location() {
+        let args = base_args();
+        let recipe = parse_runtime_recipe(r#"{"steps": [{"repeat": 1, "speed": 1.0}]}"#).unwrap();
+        let moved = Args {
+            input_files: vec![PathBuf::from("other.wav")],
+            output_dir: PathBuf::from("elsewhere"),
+            max_parallel_files: 4,
+            quiet: true,
+            ..args.clone()
+        };
+        assert_eq!(
+            args.settings_hash(&recipe).unwrap(),
+            moved.settings_hash(&recipe).unwrap()
+        );
+        let mut slower = recipe.clone();
+        slower.steps[0].speed_factor = 0.5;
+        assert_ne!(
+            args.settings_hash(&recipe).unwrap(),
+            args.settings_hash(&slower).unwrap()
+        );
+    }
+
+    #[test]
+    fn chunk_dirs_auto_pad_to_chunk_count() {
+        let settings = base_args().output_settings(Path::new("test.wav")).unwrap();
+        assert_eq!(settings.chunk_dir(1, 12), PathBuf::from("output/chunk_01"));
+        assert_eq!(settings.chunk_dir(12, 12), PathBuf::from("output/chunk_12"));
+
+        let padded = Args {
+            pad_width: Some(4),
+            ..base_args()
+        };
+        let settings = padded.output_settings(Path::new("test.wav")).unwrap();
+        assert_eq!(
+            settings.chunk_dir(1, 12),
+            PathBuf::from("output/chunk_0001")
+        );
+    }
+
+    #[test]
+    fn rerun_with_wider_padding_removes_old_chunk_folders() {
+        let root = std::env::temp_dir().join(format!("flowalyzer_repad_{}", std::process::id()));
+        let args = Args {
+            output_dir: root.clone(),
+            ..base_args()
+        };
+        let input = Path::new("test.wav");
+        let output = args.output_settings(input).unwrap();
+        let entry = |number: usize, total: usize| {
+            let dir = output.chunk_dir(number, total);
+            fs::create_dir_all(&dir).unwrap();
+            manifest::ChunkEntry {
+                path: dir.strip_prefix(&root).unwrap().join("processed.wav"),
+                text: String::new(),
+                source_start: 0.0,
+                source_end: 0.0,
+            }
+        };
+
+        let first_run: Vec<_> = (1..=9).map(|n| entry(n, 9)).collect();
+        write_file_manifest(input, &output, "hash", first_run).unwrap();
+        let second_run: Vec<_> = (1..=10).map(|n| entry(n, 10)).collect();
+        write_file_manifest(input, &output, "hash", second_run).unwrap();
+
+        let exists = |name: &str| root.join(name).exists();
+        let (old, new) = (exists("chunk_1"), exists("chunk_01"));
+        fs::remove_dir_all(&root).unwrap();
+        assert!(!old);
+        assert!(new);
+    }
+
     #[test]
     fn preview_text_leaves_short_strings() {
         let preview = format_preview_text("Hello world");
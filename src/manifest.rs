@@ -0,0 +1,152 @@
+//! Run manifests - record which settings produced an output folder
+//!
+//! A `<stem>.manifest.json` is written next to a file's chunk directories once it
+//! has been fully processed, so batch runs with `--resume` can skip files whose
+//! outputs already match the current settings. Naming it by stem keeps inputs
+//! whose chunks share a folder from overwriting each other's manifest.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const MANIFEST_SUFFIX: &str = ".manifest.json";
+
+/// Summary of a completed file run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Manifest {
+    pub input: PathBuf,
+    pub settings_hash: String,
+    #[serde(default)]
+    pub chunks: Vec<ChunkEntry>,
+}
+
+/// A written chunk, with its audio path relative to the manifest's folder
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChunkEntry {
+    pub path: PathBuf,
+    pub text: String,
+    /// Range of the input file this chunk maps to, in seconds; offset by any `--start`
+    /// trim so it points into the original input, not the trimmed audio
+    #[serde(default)]
+    pub source_start: f64,
+    #[serde(default)]
+    pub source_end: f64,
+}
+
+/// Stable FNV-1a hash of a settings description, as hex
+pub fn settings_hash(description: &str) -> String {
+    let hash = description
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+        });
+    format!("{:016x}", hash)
+}
+
+/// Manifest location for the input with `stem` whose chunks live in `dir`
+pub fn manifest_path(dir: &Path, stem: &str) -> PathBuf {
+    dir.join(format!("{}{}", stem, MANIFEST_SUFFIX))
+}
+
+/// Write `manifest` to `path`, creating its folder if needed
+pub fn write_manifest(path: &Path, manifest: &Manifest) -> Result<()> {
+    let json = serde_json::to_string_pretty(manifest).context("Failed to serialize manifest")?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("Failed to create {:?}", dir))?;
+    }
+    fs::write(path, json).with_context(|| format!("Failed to write manifest {:?}", path))
+}
+
+/// Read the manifest at `path`
+pub fn read_manifest(path: &Path) -> Result<Manifest> {
+    let json =
+        fs::read_to_string(path).with_context(|| format!("Failed to read manifest {:?}", path))?;
+    serde_json::from_str(&json).with_context(|| format!("Failed to parse manifest {:?}", path))
+}
+
+/// Whether `path` holds a manifest for `input` produced with `settings_hash`
+pub fn is_up_to_date(path: &Path, input: &Path, settings_hash: &str) -> bool {
+    read_manifest(path)
+        .is_ok_and(|manifest| manifest.input == input && manifest.settings_hash == settings_hash)
+}
+
+/// Chunk folders (relative to the manifest) that `previous` lists but `current` no
+/// longer writes, such as `chunk_1` after a re-run pads names to `chunk_01`
+pub fn stale_chunk_dirs(previous: &Manifest, current: &[ChunkEntry]) -> Vec<PathBuf> {
+    let dir_of = |entry: &ChunkEntry| entry.path.parent().map(Path::to_path_buf);
+    let kept: HashSet<PathBuf> = current.iter().filter_map(dir_of).collect();
+    previous
+        .chunks
+        .iter()
+        .filter_map(dir_of)
+        .filter(|dir| {
+            // Never reach outside the manifest's folder, whatever the file says
+            dir.components().next().is_some()
+                && dir.components().all(|c| matches!(c, Component::Normal(_)))
+                && !kept.contains(dir)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_is_stable_and_sensitive() {
+        assert_eq!(settings_hash("abc"), settings_hash("abc"));
+        assert_ne!(settings_hash("abc"), settings_hash("abd"));
+        assert_eq!(settings_hash(""), "cbf29ce484222325");
+    }
+
+    #[test]
+    fn manifest_round_trip_matches_input_and_hash() {
+        let dir = std::env::temp_dir().join(format!("flowalyzer_manifest_{}", std::process::id()));
+        let path = manifest_path(&dir, "talk");
+        let input = Path::new("talk.wav");
+        let manifest = Manifest {
+            input: input.to_path_buf(),
+            settings_hash: settings_hash("settings"),
+            chunks: Vec::new(),
+        };
+        assert!(!is_up_to_date(&path, input, &manifest.settings_hash));
+        write_manifest(&path, &manifest).unwrap();
+        assert!(is_up_to_date(&path, input, &manifest.settings_hash));
+        assert!(!is_up_to_date(&path, input, &settings_hash("other")));
+        assert!(!is_up_to_date(
+            &path,
+            Path::new("other/talk.wav"),
+            &manifest.settings_hash
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn stale_dirs_are_previous_chunks_no_longer_written() {
+        let entry = |path: &str| ChunkEntry {
+            path: PathBuf::from(path),
+            text: String::new(),
+            source_start: 0.0,
+            source_end: 0.0,
+        };
+        let previous = Manifest {
+            input: PathBuf::from("talk.wav"),
+            settings_hash: settings_hash("settings"),
+            chunks: vec![
+                entry("chunk_1/processed.wav"),
+                entry("chunk_10/processed.wav"),
+                entry("../escape/processed.wav"),
+            ],
+        };
+        let current = [
+            entry("chunk_01/processed.wav"),
+            entry("chunk_10/processed.wav"),
+        ];
+        assert_eq!(
+            stale_chunk_dirs(&previous, &current),
+            vec![PathBuf::from("chunk_1")]
+        );
+    }
+}
@@ -0,0 +1,272 @@
+//! Output estimation - predict rendered length and size without running a recipe
+//!
+//! Mirrors `apply_recipe` and the assembler's joins on durations alone, so long
+//! files can be sized up before any audio is stretched or written.
+
+use crate::audio::assembler::crossfade_samples;
+use crate::types::{ChunkBoundary, Recipe};
+
+/// Size of the canonical 16-bit PCM WAV header written per chunk file
+const WAV_HEADER_BYTES: u64 = 44;
+
+/// How rendered pieces and chunks are joined, as passed to the assembler and single-file track
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct JoinSettings {
+    /// Crossfade between consecutive recipe pieces, in milliseconds
+    pub crossfade_ms: f64,
+    /// Butt-join identical consecutive pieces instead of crossfading them
+    pub butt_join_repeats: bool,
+    /// Gap between chunks when they are joined into one track, in seconds
+    pub single_file_gap: Option<f64>,
+}
+
+/// Predicted output of applying a recipe to a set of chunks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutputEstimate {
+    /// Number of chunks rendered
+    pub chunks: usize,
+    /// Number of files written: one per chunk, or one joined track
+    pub files: usize,
+    /// Total rendered audio across all files, in seconds
+    pub seconds: f64,
+}
+
+impl OutputEstimate {
+    /// Approximate bytes on disk as 16-bit WAV at `sample_rate` with `channels` channels
+    pub fn wav_bytes(&self, sample_rate: u32, channels: u16) -> u64 {
+        let frames = (self.seconds * sample_rate as f64).round() as u64;
+        frames * channels as u64 * 2 + WAV_HEADER_BYTES * self.files as u64
+    }
+}
+
+/// One rendered piece of a chunk, and whether it is a bit-identical copy of the piece before
+struct Piece {
+    seconds: f64,
+    repeats_previous: bool,
+}
+
+/// Estimate the rendered duration of `recipe` over every boundary
+///
+/// Each step contributes `repeat_count` copies of the speed-adjusted chunk
+/// (audio or silence), plus `repeat_gap_ms` between consecutive audio repeats, or
+/// a repeat-length response gap between them when `interleave_silence` is set.
+/// `pauses` holds each chunk's natural pause, used for silent steps when the recipe
+/// sets `natural_pause_scale`.
+///
+/// Pieces overlap by one crossfade at `sample_rate`, except copies of a repeat that
+/// `butt_join_repeats` joins without a fade. Identical pieces from different steps are
+/// still counted as crossfaded, so the estimate may run one fade short there. With a
+/// single-file gap, chunks are joined into one track separated by that gap.
+pub fn estimate_recipe_output(
+    boundaries: &[ChunkBoundary],
+    pauses: &[Option<f64>],
+    recipe: &Recipe,
+    sample_rate: u32,
+    joins: &JoinSettings,
+) -> OutputEstimate {
+    let crossfade =
+        crossfade_samples(sample_rate, joins.crossfade_ms) as f64 / sample_rate.max(1) as f64;
+    let rendered: f64 = boundaries
+        .iter()
+        .enumerate()
+        .map(|(index, boundary)| {
+            let duration = (boundary.end_time - boundary.start_time).max(0.0);
+            let natural_pause = pauses.get(index).copied().flatten();
+            recipe_pieces(recipe, duration, natural_pause)
+                .into_iter()
+                .fold(None, |total: Option<f64>, piece| {
+                    Some(match total {
+                        None => piece.seconds,
+                        Some(total) if piece.repeats_previous && joins.butt_join_repeats => {
+                            total + piece.seconds
+                        }
+                        Some(total) => {
+                            total + piece.seconds - crossfade.min(piece.seconds).min(total)
+                        }
+                    })
+                })
+                .unwrap_or(0.0)
+        })
+        .sum();
+    let (files, gaps) = match joins.single_file_gap {
+        Some(gap) => (
+            boundaries.len().min(1),
+            boundaries.len().saturating_sub(1) as f64 * gap.max(0.0),
+        ),
+        None => (boundaries.len(), 0.0),
+    };
+    OutputEstimate {
+        chunks: boundaries.len(),
+        files,
+        seconds: rendered + gaps,
+    }
+}
+
+/// The pieces `apply_recipe` renders for a chunk of `duration` seconds, in order
+fn recipe_pieces(recipe: &Recipe, duration: f64, natural_pause: Option<f64>) -> Vec<Piece> {
+    let natural_silence = recipe.natural_silence(natural_pause);
+    let piece = |seconds, repeats_previous| Piece {
+        seconds,
+        repeats_previous,
+    };
+    let mut pieces = Vec::new();
+    for step in &recipe.steps {
+        let adjusted = duration / step.speed_factor as f64;
+        for idx in 0..step.repeat_count {
+            if step.silent {
+                // Faded silences take on their neighbours' edges, so only unfaded copies match
+                let copy = idx > 0 && recipe.silence_fade_ms <= 0.0;
+                pieces.push(piece(natural_silence.unwrap_or(adjusted), copy));
+            } else if idx == 0 {
+                pieces.push(piece(adjusted, false));
+            } else if step.interleave_silence {
+                pieces.push(piece(adjusted, false));
+                pieces.push(piece(adjusted, false));
+            } else if step.repeat_gap_ms > 0.0 {
+                pieces.push(piece(step.repeat_gap_ms / 1000.0, false));
+                pieces.push(piece(adjusted, false));
+            } else {
+                pieces.push(piece(adjusted, true));
+            }
+        }
+    }
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::assembler::assemble_audio;
+    use crate::operations::recipe::apply_recipe;
+    use crate::types::{AudioChunk, RecipeStep};
+
+    fn boundary(start_time: f64, end_time: f64) -> ChunkBoundary {
+        ChunkBoundary {
+            start_time,
+            end_time,
+            source_segment_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn estimate_accounts_for_speed_repeats_gaps_and_silence() {
+        let boundaries = vec![boundary(0.0, 2.0), boundary(2.0, 3.0)];
+        let recipe = Recipe::new("estimate")
+            .add_step(RecipeStep {
+                repeat_gap_ms: 500.0,
+                ..RecipeStep::audio(2, 0.5)
+            })
+            .add_step(RecipeStep::silence(1, 1.0));
+
+        let estimate =
+            estimate_recipe_output(&boundaries, &[], &recipe, 1_000, &JoinSettings::default());
+
+        // 2s chunk: 2 x 4s + 0.5s gap + 2s silence; 1s chunk: 2 x 2s + 0.5s gap + 1s silence
+        assert_eq!(estimate.chunks, 2);
+        assert!((estimate.seconds - 16.0).abs() < 1e-9);
+        assert_eq!(estimate.wav_bytes(1_000, 2), 16_000 * 2 * 2 + 88);
+    }
+
+    #[test]
+    fn estimate_uses_natural_pauses_for_silent_steps() {
+        let boundaries = vec![boundary(0.0, 2.0), boundary(2.8, 4.0)];
+        let mut recipe = Recipe::new("natural").add_step(RecipeStep::silence(1, 1.0));
+        recipe.natural_pause_scale = Some(2.0);
+
+        let estimate = estimate_recipe_output(
+            &boundaries,
+            &[Some(0.8), None],
+            &recipe,
+            1_000,
+            &JoinSettings::default(),
+        );
+
+        // 0.8s pause x 2 for the first chunk; the last chunk keeps its 1.2s length
+        assert!((estimate.seconds - 2.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn estimate_deducts_crossfades_except_butt_joined_repeats() {
+        let boundaries = vec![boundary(0.0, 1.0)];
+        let recipe = Recipe::new("joins")
+            .add_step(RecipeStep::audio(2, 1.0))
+            .add_step(RecipeStep::silence(1, 1.0));
+        let joins = JoinSettings {
+            crossfade_ms: 100.0,
+            ..JoinSettings::default()
+        };
+
+        let faded = estimate_recipe_output(&boundaries, &[], &recipe, 1_000, &joins);
+        let butted = estimate_recipe_output(
+            &boundaries,
+            &[],
+            &recipe,
+            1_000,
+            &JoinSettings {
+                butt_join_repeats: true,
+                ..joins
+            },
+        );
+
+        // Three 1s pieces: two 0.1s overlaps, or one when the repeat is butt-joined
+        assert!((faded.seconds - 2.8).abs() < 1e-9);
+        assert!((butted.seconds - 2.9).abs() < 1e-9);
+    }
+
+    #[test]
+    fn single_file_estimate_adds_gaps_and_writes_one_file() {
+        let boundaries = vec![boundary(0.0, 1.0), boundary(1.0, 2.0), boundary(2.0, 3.0)];
+        let recipe = Recipe::new("track").add_step(RecipeStep::audio(1, 1.0));
+        let joins = JoinSettings {
+            single_file_gap: Some(0.5),
+            ..JoinSettings::default()
+        };
+
+        let estimate = estimate_recipe_output(&boundaries, &[], &recipe, 1_000, &joins);
+
+        assert_eq!(estimate.chunks, 3);
+        assert_eq!(estimate.files, 1);
+        assert!((estimate.seconds - 4.0).abs() < 1e-9);
+        assert_eq!(estimate.wav_bytes(1_000, 1), 4_000 * 2 + 44);
+    }
+
+    #[test]
+    fn estimate_matches_assembled_length() {
+        let chunk = AudioChunk {
+            samples: (0..500).map(|i| (i as f32 * 0.05).sin() * 0.5).collect(),
+            sample_rate: 1_000,
+            start_time: 0.0,
+            end_time: 0.5,
+        };
+        // Silences separate the steps: identical audio from two steps would also butt-join
+        let recipe = Recipe::new("mixed")
+            .add_step(RecipeStep::audio(3, 1.0))
+            .add_step(RecipeStep::silence(2, 1.0))
+            .add_step(RecipeStep {
+                repeat_gap_ms: 200.0,
+                ..RecipeStep::audio(2, 1.0)
+            })
+            .add_step(RecipeStep::silence(1, 1.0))
+            .add_step(RecipeStep {
+                interleave_silence: true,
+                ..RecipeStep::audio(2, 1.0)
+            });
+
+        for butt_join_repeats in [false, true] {
+            let joins = JoinSettings {
+                crossfade_ms: 20.0,
+                butt_join_repeats,
+                single_file_gap: None,
+            };
+            let pieces = apply_recipe(&chunk, &recipe, None);
+            let assembled = assemble_audio(&pieces, joins.crossfade_ms, butt_join_repeats).unwrap();
+            let estimate =
+                estimate_recipe_output(&[boundary(0.0, 0.5)], &[], &recipe, 1_000, &joins);
+
+            assert_eq!(
+                (estimate.seconds * 1_000.0).round() as usize,
+                assembled.samples.len()
+            );
+        }
+    }
+}
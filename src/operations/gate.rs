@@ -0,0 +1,113 @@
+//! Noise gate operation - attenuate low-level hiss between and after words
+//!
+//! Pure function module following "bricks & studs" philosophy:
+//! - Takes AudioChunk and gate settings as input
+//! - Returns a gated AudioChunk of the same length
+//! - No side effects
+//!
+//! Gain ramps toward open or closed with separate attack and release times,
+//! so the gate doesn't chatter on signals hovering around the threshold.
+
+use crate::types::{AudioChunk, NoiseGate};
+
+/// How quickly the level detector falls after a peak
+const DETECTOR_RELEASE_MS: f32 = 10.0;
+
+/// Silence samples whose detected level stays below `gate.threshold_db`
+///
+/// # Arguments
+/// * `chunk` - The audio chunk to gate
+/// * `gate` - Threshold in dBFS plus attack/release times in milliseconds
+///
+/// # Returns
+/// AudioChunk with the same timing, with sub-threshold regions faded to silence
+pub fn apply_noise_gate(chunk: &AudioChunk, gate: &NoiseGate) -> AudioChunk {
+    let threshold = 10f32.powf(gate.threshold_db / 20.0);
+    let detector_decay = decay_per_sample(DETECTOR_RELEASE_MS, chunk.sample_rate);
+    let attack = 1.0 - decay_per_sample(gate.attack_ms, chunk.sample_rate);
+    let release = 1.0 - decay_per_sample(gate.release_ms, chunk.sample_rate);
+
+    let mut level = 0.0f32;
+    let mut gain = 0.0f32;
+    let samples = chunk
+        .samples
+        .iter()
+        .map(|&sample| {
+            level = sample.abs().max(level * detector_decay);
+            let (target, rate) = if level >= threshold {
+                (1.0, attack)
+            } else {
+                (0.0, release)
+            };
+            gain += (target - gain) * rate;
+            sample * gain
+        })
+        .collect();
+
+    AudioChunk {
+        samples,
+        ..chunk.clone()
+    }
+}
+
+/// One-pole coefficient that decays to ~37% over `time_ms`
+fn decay_per_sample(time_ms: f32, sample_rate: u32) -> f32 {
+    let samples = time_ms / 1000.0 * sample_rate as f32;
+    if samples <= 1.0 {
+        0.0
+    } else {
+        (-1.0 / samples).exp()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gate() -> NoiseGate {
+        NoiseGate {
+            threshold_db: -40.0,
+            attack_ms: 1.0,
+            release_ms: 20.0,
+        }
+    }
+
+    #[test]
+    fn hiss_is_attenuated_while_speech_passes() {
+        let sample_rate = 10_000;
+        let tone = |len: usize, amp: f32| (0..len).map(move |i| amp * (i as f32 * 0.3).sin());
+        // 0.5s speech-level tone, then 0.5s of hiss at about -50 dBFS
+        let samples: Vec<f32> = tone(5_000, 0.5).chain(tone(5_000, 0.003)).collect();
+        let chunk = AudioChunk {
+            samples: samples.clone(),
+            sample_rate,
+            start_time: 0.0,
+            end_time: 1.0,
+        };
+
+        let gated = apply_noise_gate(&chunk, &gate());
+
+        assert_eq!(gated.samples.len(), samples.len());
+        let passed = gated.samples[1_000..4_900]
+            .iter()
+            .zip(&samples[1_000..4_900]);
+        for (gated, original) in passed {
+            assert!((gated - original).abs() < 1e-3);
+        }
+        let tail_peak = gated.samples[7_000..]
+            .iter()
+            .fold(0.0f32, |peak, s| peak.max(s.abs()));
+        assert!(tail_peak < 1e-5);
+    }
+
+    #[test]
+    fn empty_chunk_stays_empty() {
+        let chunk = AudioChunk {
+            samples: Vec::new(),
+            sample_rate: 44_100,
+            start_time: 0.0,
+            end_time: 0.0,
+        };
+        assert!(apply_noise_gate(&chunk, &gate()).samples.is_empty());
+    }
+}
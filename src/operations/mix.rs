@@ -0,0 +1,102 @@
+//! Mix operation - lay a quiet bed of audio under a chunk
+//!
+//! Pure function module following "bricks & studs" philosophy:
+//! - Takes AudioChunk, bed samples and a gain as input
+//! - Returns a mixed AudioChunk of the same length
+//! - No side effects
+
+use crate::audio::resample::linear_resample;
+use crate::types::{AudioChunk, MixBed};
+
+/// Add `bed` under `chunk` at `gain_db`, clamping the sum to [-1.0, 1.0]
+///
+/// # Arguments
+/// * `chunk` - The audio chunk to mix into
+/// * `bed` - Samples at the chunk's rate, aligned to its start; a shorter bed
+///   leaves the rest of the chunk untouched, a longer one is cut off
+/// * `gain_db` - Bed level relative to full scale, in dB
+///
+/// # Returns
+/// AudioChunk with the same length and timing as `chunk`
+pub fn mix_chunk(chunk: &AudioChunk, bed: &[f32], gain_db: f32) -> AudioChunk {
+    let gain = 10f32.powf(gain_db / 20.0);
+    let mut samples = chunk.samples.clone();
+    for (sample, &under) in samples.iter_mut().zip(bed) {
+        *sample = (*sample + under * gain).clamp(-1.0, 1.0);
+    }
+    AudioChunk {
+        samples,
+        ..chunk.clone()
+    }
+}
+
+/// The bed samples that sit under `chunk`, at the chunk's sample rate
+///
+/// A track bed is cut at the chunk's source time range, so a backing track aligned
+/// with the input lines up with each chunk. Chunk times are relative to the untrimmed
+/// input, so a `--start` trim is already accounted for.
+pub fn bed_samples(chunk: &AudioChunk, bed: &MixBed) -> Vec<f32> {
+    match bed {
+        MixBed::Chunk => chunk.samples.clone(),
+        MixBed::Track { audio } => {
+            let rate = audio.sample_rate as f64;
+            let to_index = |seconds: f64| {
+                ((seconds * rate).round().max(0.0) as usize).min(audio.samples.len())
+            };
+            let start = to_index(chunk.start_time);
+            let end = to_index(chunk.end_time).max(start);
+            linear_resample(
+                &audio.samples[start..end],
+                audio.sample_rate,
+                chunk.sample_rate,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AudioData;
+    use std::sync::Arc;
+
+    fn chunk(samples: Vec<f32>, start_time: f64, end_time: f64) -> AudioChunk {
+        AudioChunk {
+            samples,
+            sample_rate: 10,
+            start_time,
+            end_time,
+        }
+    }
+
+    #[test]
+    fn test_mix_is_gain_weighted_sum_clamped() {
+        let voice = chunk(vec![0.5, -0.5, 0.9, 0.0], 0.0, 0.4);
+        let bed = [0.2, 0.4, 0.4];
+
+        let mixed = mix_chunk(&voice, &bed, -6.0);
+
+        let gain = 10f32.powf(-6.0 / 20.0);
+        let expected = [0.5 + 0.2 * gain, -0.5 + 0.4 * gain, 1.0, 0.0];
+        for (actual, expected) in mixed.samples.iter().zip(expected) {
+            assert!((actual - expected).abs() < 1e-6);
+        }
+        assert_eq!(mixed.start_time, voice.start_time);
+    }
+
+    #[test]
+    fn test_track_bed_is_cut_at_chunk_source_range() {
+        let track = MixBed::Track {
+            audio: Arc::new(AudioData {
+                samples: (0..20).map(|i| i as f32).collect(),
+                sample_rate: 10,
+            }),
+        };
+
+        let bed = bed_samples(&chunk(vec![0.0; 5], 0.5, 1.0), &track);
+        assert_eq!(bed, vec![5.0, 6.0, 7.0, 8.0, 9.0]);
+
+        let past_end = bed_samples(&chunk(vec![0.0; 5], 3.0, 3.5), &track);
+        assert!(past_end.is_empty());
+    }
+}
@@ -1,9 +1,16 @@
+pub mod estimate;
+pub mod gate;
+pub mod mix;
+pub mod pan;
 pub mod recipe;
 pub mod repeat;
 pub mod silence;
 pub mod speed;
+pub mod wsola;
 
 // Re-export operation functions for convenience
-pub use repeat::repeat_chunk;
-pub use silence::insert_silence;
+pub use gate::apply_noise_gate;
+pub use mix::{bed_samples, mix_chunk};
+pub use repeat::repeat_chunk_with_gap;
+pub use silence::{fade_silence_edges, insert_silence_with_floor};
 pub use speed::change_speed;
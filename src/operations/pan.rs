@@ -0,0 +1,83 @@
+//! Pan operation - sweeps a mono chunk across the stereo field
+//!
+//! Pure function module following "bricks & studs" philosophy:
+//! - Takes rendered AudioData and start/end pan positions as input
+//! - Returns a StereoChunk with per-sample equal-power panning
+//! - No side effects
+
+use crate::types::{AudioData, StereoChunk};
+use std::f32::consts::FRAC_PI_4;
+
+/// Pans mono audio from `start` to `end` across its duration
+///
+/// # Arguments
+/// * `audio` - The mono audio to pan
+/// * `start` - Pan position at the first sample (-1.0 = hard left, 1.0 = hard right)
+/// * `end` - Pan position at the last sample, linearly interpolated in between
+///
+/// # Returns
+/// StereoChunk with the same length and sample rate as the input
+pub fn pan_chunk(audio: &AudioData, start: f32, end: f32) -> StereoChunk {
+    let last = audio.samples.len().saturating_sub(1).max(1) as f32;
+    let (left, right) = audio
+        .samples
+        .iter()
+        .enumerate()
+        .map(|(idx, &sample)| {
+            let position = start + (end - start) * idx as f32 / last;
+            let (left_gain, right_gain) = pan_gains(position);
+            (sample * left_gain, sample * right_gain)
+        })
+        .unzip();
+
+    StereoChunk {
+        left,
+        right,
+        sample_rate: audio.sample_rate,
+    }
+}
+
+/// Equal-power gains for a pan position, keeping perceived loudness constant
+fn pan_gains(position: f32) -> (f32, f32) {
+    let angle = (position.clamp(-1.0, 1.0) + 1.0) * FRAC_PI_4;
+    (angle.cos(), angle.sin())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constant_chunk(len: usize) -> AudioData {
+        AudioData {
+            samples: vec![0.5; len],
+            sample_rate: 100,
+        }
+    }
+
+    #[test]
+    fn sweep_moves_from_left_to_right() {
+        let panned = pan_chunk(&constant_chunk(100), -1.0, 1.0);
+        assert_eq!(panned.left.len(), 100);
+        assert_eq!(panned.right.len(), 100);
+        assert!(panned.left[0] > panned.right[0]);
+        assert!(panned.right[99] > panned.left[99]);
+        assert!(panned.right[0].abs() < 1e-6);
+        assert!(panned.left[99].abs() < 1e-6);
+    }
+
+    #[test]
+    fn center_position_splits_evenly() {
+        let panned = pan_chunk(&constant_chunk(4), 0.0, 0.0);
+        for (left, right) in panned.left.iter().zip(&panned.right) {
+            assert!((left - right).abs() < 1e-6);
+        }
+        assert_eq!(panned.sample_rate, 100);
+    }
+
+    #[test]
+    fn empty_chunk_stays_empty() {
+        let panned = pan_chunk(&constant_chunk(0), -1.0, 1.0);
+        assert!(panned.left.is_empty());
+        assert!(panned.right.is_empty());
+    }
+}
@@ -6,67 +6,151 @@
 //! - No side effects
 //! - Reuses existing operation functions
 
-use super::{change_speed, insert_silence, repeat_chunk};
+use super::{
+    apply_noise_gate, bed_samples, change_speed, fade_silence_edges, insert_silence_with_floor,
+    mix_chunk, repeat_chunk_with_gap,
+};
 use crate::types::{AudioChunk, Recipe};
 
-/// Apply a recipe (sequence of operations) to a single audio chunk
+/// Apply a recipe (sequence of operations) to a chunk the speaker followed with
+/// `natural_pause` seconds of quiet
 ///
 /// For each step in the recipe:
 /// 1. Compute a speed-adjusted view of the original chunk
-/// 2. If `silent` is false, repeat that audio `repeat_count` times
+/// 2. If `silent` is false, repeat that audio `repeat_count` times, separated by
+///    `repeat_gap_ms` of silence
+///    (or, with `interleave_silence`, by silence matching the adjusted duration)
 /// 3. If `silent` is true, emit `repeat_count` silence chunks matching the adjusted duration
 ///
+/// Silence and gaps carry comfort noise at `recipe.silence_floor` RMS when it is non-zero,
+/// and ramp from and to the neighbouring audio over `recipe.silence_fade_ms` at their edges.
+/// When `recipe.noise_gate` is set, the chunk is gated once before any step.
+/// When `recipe.mix` is set, its bed is mixed under every audio repeat.
+///
+/// With `recipe.natural_pause_scale` set and a known pause, silent steps last
+/// `natural_pause * scale` instead of the speed-adjusted chunk duration, preserving
+/// the speaker's pacing.
+///
 /// # Arguments
 /// * `chunk` - The audio chunk to process
 /// * `recipe` - The recipe defining the sequence of operations
+/// * `natural_pause` - Quiet after the chunk in the source, when known
 ///
 /// # Returns
 /// Vector of audio chunks representing all operations applied
 ///
 /// # Example
 /// ```
-/// use flowalyzer::types::{AudioChunk, Recipe};
+/// use flowalyzer::types::{AudioChunk, Recipe, RecipeStep};
 /// use flowalyzer::operations::recipe::apply_recipe;
 ///
 /// let chunk = AudioChunk { /* ... */ };
 /// let recipe = Recipe::new("example")
-///     .add_step(RecipeStep {
-///         repeat_count: 3,
-///         speed_factor: 0.75,
-///         silent: false,
-///     })
-///     .add_step(RecipeStep {
-///         repeat_count: 1,
-///         speed_factor: 0.75,
-///         silent: true,
-///     });
-/// let results = apply_recipe(&chunk, &recipe);
+///     .add_step(RecipeStep::audio(3, 0.75))
+///     .add_step(RecipeStep::silence(1, 0.75));
+/// let results = apply_recipe(&chunk, &recipe, None);
 /// assert_eq!(results.len(), 4);
 /// ```
-pub fn apply_recipe(chunk: &AudioChunk, recipe: &Recipe) -> Vec<AudioChunk> {
+pub fn apply_recipe(
+    chunk: &AudioChunk,
+    recipe: &Recipe,
+    natural_pause: Option<f64>,
+) -> Vec<AudioChunk> {
+    let natural_silence = recipe.natural_silence(natural_pause);
+    let gated = recipe.noise_gate.map(|gate| apply_noise_gate(chunk, &gate));
+    let chunk = gated.as_ref().unwrap_or(chunk);
+    let bed = recipe
+        .mix
+        .as_ref()
+        .map(|mix| (bed_samples(chunk, &mix.bed), mix.gain_db));
     let mut results = Vec::new();
+    let mut silences = Vec::new();
 
     for step in &recipe.steps {
-        let speed_adjusted = change_speed(chunk, step.speed_factor);
+        let speed_adjusted = change_speed(chunk, step.speed_factor, recipe.speed_algorithm);
+        let speed_adjusted = match &bed {
+            Some((bed, gain_db)) if !step.silent => mix_chunk(&speed_adjusted, bed, *gain_db),
+            _ => speed_adjusted,
+        };
         if step.silent {
-            let silence_duration = speed_adjusted.end_time - speed_adjusted.start_time;
+            let silence_duration =
+                natural_silence.unwrap_or(speed_adjusted.end_time - speed_adjusted.start_time);
             for _ in 0..step.repeat_count {
-                results.push(insert_silence(silence_duration, speed_adjusted.sample_rate));
+                silences.push(results.len());
+                results.push(insert_silence_with_floor(
+                    silence_duration,
+                    speed_adjusted.sample_rate,
+                    recipe.silence_floor,
+                ));
+            }
+        } else if step.interleave_silence {
+            let response = insert_silence_with_floor(
+                speed_adjusted.end_time - speed_adjusted.start_time,
+                speed_adjusted.sample_rate,
+                recipe.silence_floor,
+            );
+            for idx in 0..step.repeat_count {
+                if idx > 0 {
+                    silences.push(results.len());
+                    results.push(response.clone());
+                }
+                results.push(speed_adjusted.clone());
             }
         } else {
-            let repeated = repeat_chunk(&speed_adjusted, step.repeat_count);
+            let repeated = repeat_chunk_with_gap(
+                &speed_adjusted,
+                step.repeat_count,
+                step.repeat_gap_ms,
+                recipe.silence_floor,
+            );
+            if step.repeat_gap_ms > 0.0 {
+                // Gaps sit between copies, at every other position
+                silences.extend((results.len() + 1..results.len() + repeated.len()).step_by(2));
+            }
             results.extend(repeated);
         }
     }
 
+    if recipe.silence_fade_ms > 0.0 {
+        fade_silences(&mut results, &silences, recipe.silence_fade_ms);
+    }
     results
 }
 
+/// Fade each silence at `silences` against the samples either side of it
+fn fade_silences(results: &mut [AudioChunk], silences: &[usize], fade_ms: f64) {
+    let edge = |idx: Option<usize>, last: bool| {
+        idx.and_then(|i| results.get(i))
+            .and_then(|chunk| {
+                if last {
+                    chunk.samples.last()
+                } else {
+                    chunk.samples.first()
+                }
+            })
+            .copied()
+            .unwrap_or(0.0)
+    };
+    // Read every neighbour before fading so adjacent silences see unfaded edges
+    let leads: Vec<(usize, f32, f32)> = silences
+        .iter()
+        .map(|&idx| {
+            (
+                idx,
+                edge(idx.checked_sub(1), true),
+                edge(Some(idx + 1), false),
+            )
+        })
+        .collect();
+    for (idx, lead_in, lead_out) in leads {
+        fade_silence_edges(&mut results[idx], lead_in, lead_out, fade_ms);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::operations::speed::change_speed;
-    use crate::types::RecipeStep;
+    use crate::types::{RecipeStep, SpeedAlgorithm};
 
     fn create_test_chunk() -> AudioChunk {
         // Create a 1-second chunk
@@ -93,7 +177,7 @@ mod tests {
         let chunk = create_test_chunk();
         let recipe = Recipe::new("empty");
 
-        let results = apply_recipe(&chunk, &recipe);
+        let results = apply_recipe(&chunk, &recipe, None);
         assert_eq!(results.len(), 0, "Empty recipe should produce no output");
     }
 
@@ -101,13 +185,9 @@ mod tests {
     fn test_apply_single_step_no_silence() {
         let chunk = create_test_chunk();
         let mut recipe = Recipe::new("single-step");
-        recipe = recipe.add_step(RecipeStep {
-            repeat_count: 2,
-            speed_factor: 1.0,
-            silent: false,
-        });
+        recipe = recipe.add_step(RecipeStep::audio(2, 1.0));
 
-        let results = apply_recipe(&chunk, &recipe);
+        let results = apply_recipe(&chunk, &recipe, None);
 
         // Should have 2 chunks (2 repeats, no silence)
         assert_eq!(results.len(), 2);
@@ -122,19 +202,17 @@ mod tests {
     fn test_apply_single_step_with_silence() {
         let chunk = create_test_chunk();
         let mut recipe = Recipe::new("with-silence");
-        recipe = recipe.add_step(RecipeStep {
-            repeat_count: 2,
-            speed_factor: 1.0,
-            silent: true,
-        });
+        recipe = recipe.add_step(RecipeStep::silence(2, 1.0));
 
-        let results = apply_recipe(&chunk, &recipe);
+        let results = apply_recipe(&chunk, &recipe, None);
 
         assert_eq!(results.len(), 2);
         assert!(results
             .iter()
             .all(|chunk| chunk.samples.iter().all(|&s| s == 0.0)));
-        let expected_len = change_speed(&chunk, 1.0).samples.len();
+        let expected_len = change_speed(&chunk, 1.0, SpeedAlgorithm::Signalsmith)
+            .samples
+            .len();
         assert!(results
             .iter()
             .all(|silence| silence.samples.len() == expected_len));
@@ -144,38 +222,14 @@ mod tests {
     fn test_apply_language_learning_recipe() {
         let chunk = create_test_chunk(); // 1-second chunk
         let recipe = Recipe::new("language-learning")
-            .add_step(RecipeStep {
-                repeat_count: 3,
-                speed_factor: 0.5,
-                silent: false,
-            })
-            .add_step(RecipeStep {
-                repeat_count: 1,
-                speed_factor: 0.5,
-                silent: true,
-            })
-            .add_step(RecipeStep {
-                repeat_count: 3,
-                speed_factor: 1.0,
-                silent: false,
-            })
-            .add_step(RecipeStep {
-                repeat_count: 1,
-                speed_factor: 1.0,
-                silent: true,
-            })
-            .add_step(RecipeStep {
-                repeat_count: 3,
-                speed_factor: 1.5,
-                silent: false,
-            })
-            .add_step(RecipeStep {
-                repeat_count: 1,
-                speed_factor: 1.5,
-                silent: true,
-            });
+            .add_step(RecipeStep::audio(3, 0.5))
+            .add_step(RecipeStep::silence(1, 0.5))
+            .add_step(RecipeStep::audio(3, 1.0))
+            .add_step(RecipeStep::silence(1, 1.0))
+            .add_step(RecipeStep::audio(3, 1.5))
+            .add_step(RecipeStep::silence(1, 1.5));
 
-        let results = apply_recipe(&chunk, &recipe);
+        let results = apply_recipe(&chunk, &recipe, None);
 
         // Expected: (3 slow + silence) + (3 normal + silence) + (3 fast + silence) = 12 chunks
         assert_eq!(
@@ -223,13 +277,9 @@ mod tests {
     fn test_recipe_silence_duration_matches_speed() {
         let chunk = create_test_chunk(); // 1-second chunk
         let mut recipe = Recipe::new("test");
-        recipe = recipe.add_step(RecipeStep {
-            repeat_count: 1,
-            speed_factor: 0.5,
-            silent: true,
-        });
+        recipe = recipe.add_step(RecipeStep::silence(1, 0.5));
 
-        let results = apply_recipe(&chunk, &recipe);
+        let results = apply_recipe(&chunk, &recipe, None);
 
         assert_eq!(results.len(), 1); // one silence chunk
 
@@ -237,7 +287,9 @@ mod tests {
             results[0].samples.iter().all(|&s| s == 0.0),
             "Silence chunk should be silent"
         );
-        let expected_len = change_speed(&chunk, 0.5).samples.len();
+        let expected_len = change_speed(&chunk, 0.5, SpeedAlgorithm::Signalsmith)
+            .samples
+            .len();
         assert_eq!(
             results[0].samples.len(),
             expected_len,
@@ -250,18 +302,10 @@ mod tests {
         let chunk = create_test_chunk();
         let mut recipe = Recipe::new("multi-speed");
         recipe = recipe
-            .add_step(RecipeStep {
-                repeat_count: 2,
-                speed_factor: 0.5,
-                silent: false,
-            })
-            .add_step(RecipeStep {
-                repeat_count: 2,
-                speed_factor: 2.0,
-                silent: false,
-            });
+            .add_step(RecipeStep::audio(2, 0.5))
+            .add_step(RecipeStep::audio(2, 2.0));
 
-        let results = apply_recipe(&chunk, &recipe);
+        let results = apply_recipe(&chunk, &recipe, None);
 
         // Should have 4 chunks total (2 slow + 2 fast)
         assert_eq!(results.len(), 4);
@@ -280,24 +324,188 @@ mod tests {
         let chunk = create_test_chunk();
         let mut recipe = Recipe::new("reuse");
         recipe = recipe
-            .add_step(RecipeStep {
-                repeat_count: 1,
-                speed_factor: 0.75,
-                silent: false,
-            })
-            .add_step(RecipeStep {
-                repeat_count: 1,
-                speed_factor: 1.5,
-                silent: false,
-            });
+            .add_step(RecipeStep::audio(1, 0.75))
+            .add_step(RecipeStep::audio(1, 1.5));
 
-        let results = apply_recipe(&chunk, &recipe);
+        let results = apply_recipe(&chunk, &recipe, None);
         assert_eq!(results.len(), 2);
 
-        let slow = change_speed(&chunk, 0.75);
-        let fast = change_speed(&chunk, 1.5);
+        let slow = change_speed(&chunk, 0.75, SpeedAlgorithm::Signalsmith);
+        let fast = change_speed(&chunk, 1.5, SpeedAlgorithm::Signalsmith);
 
         assert_eq!(results[0].samples, slow.samples);
         assert_eq!(results[1].samples, fast.samples);
     }
+
+    #[test]
+    fn test_recipe_repeat_gap_inserts_silence_between_repeats() {
+        let chunk = create_test_chunk();
+        let recipe = Recipe::new("gapped").add_step(RecipeStep {
+            repeat_gap_ms: 250.0,
+            ..RecipeStep::audio(2, 1.0)
+        });
+
+        let results = apply_recipe(&chunk, &recipe, None);
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].samples, chunk.samples);
+        assert_eq!(results[1].samples.len(), 11025);
+        assert!(results[1].samples.iter().all(|&s| s == 0.0));
+        assert_eq!(results[2].samples, chunk.samples);
+    }
+
+    #[test]
+    fn test_recipe_silence_floor_fills_silences_and_gaps() {
+        let chunk = create_test_chunk();
+        let mut recipe = Recipe::new("room-tone")
+            .add_step(RecipeStep {
+                repeat_gap_ms: 100.0,
+                ..RecipeStep::audio(2, 1.0)
+            })
+            .add_step(RecipeStep::silence(1, 1.0));
+        recipe.silence_floor = 0.001;
+
+        let results = apply_recipe(&chunk, &recipe, None);
+
+        assert_eq!(results.len(), 4);
+        for silence in [&results[1], &results[3]] {
+            assert!(silence.samples.iter().any(|&s| s != 0.0));
+            assert!(silence.samples.iter().all(|&s| s.abs() < 0.01));
+        }
+    }
+
+    #[test]
+    fn test_recipe_noise_gate_runs_before_steps() {
+        let hiss = AudioChunk {
+            samples: vec![0.001; 4410],
+            sample_rate: 44100,
+            start_time: 0.0,
+            end_time: 0.1,
+        };
+        let mut recipe = Recipe::new("gated").add_step(RecipeStep::audio(2, 1.0));
+        recipe.noise_gate = Some(crate::types::NoiseGate {
+            threshold_db: -40.0,
+            attack_ms: 5.0,
+            release_ms: 80.0,
+        });
+
+        let results = apply_recipe(&hiss, &recipe, None);
+
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|result| result.samples.iter().all(|&s| s == 0.0)));
+    }
+
+    #[test]
+    fn test_recipe_interleaved_repeats_alternate_audio_and_silence() {
+        let chunk = create_test_chunk();
+        let recipe = Recipe::new("call-and-response").add_step(RecipeStep {
+            interleave_silence: true,
+            ..RecipeStep::audio(3, 1.0)
+        });
+
+        let results = apply_recipe(&chunk, &recipe, None);
+
+        assert_eq!(results.len(), 5);
+        for (idx, result) in results.iter().enumerate() {
+            assert_eq!(result.samples.len(), chunk.samples.len());
+            let silent = result.samples.iter().all(|&s| s == 0.0);
+            assert_eq!(silent, idx % 2 == 1);
+        }
+    }
+
+    #[test]
+    fn test_recipe_natural_pause_scales_silent_steps() {
+        let chunk = create_test_chunk();
+        let mut recipe = Recipe::new("natural")
+            .add_step(RecipeStep::audio(1, 1.0))
+            .add_step(RecipeStep::silence(1, 0.5));
+
+        let default_silence = apply_recipe(&chunk, &recipe, Some(0.8));
+        assert_eq!(
+            default_silence[1].samples.len(),
+            change_speed(&chunk, 0.5, SpeedAlgorithm::Signalsmith)
+                .samples
+                .len()
+        );
+
+        recipe.natural_pause_scale = Some(1.5);
+        let results = apply_recipe(&chunk, &recipe, Some(0.8));
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].samples.len(), 52_920); // 0.8s x 1.5 at 44.1kHz
+        assert!(results[1].samples.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_recipe_silence_fade_ramps_gap_edges_to_neighbours() {
+        let chunk = AudioChunk {
+            samples: vec![0.5; 100],
+            sample_rate: 1000,
+            start_time: 0.0,
+            end_time: 0.1,
+        };
+        let mut recipe = Recipe::new("faded")
+            .add_step(RecipeStep {
+                repeat_gap_ms: 50.0,
+                ..RecipeStep::audio(2, 1.0)
+            })
+            .add_step(RecipeStep::silence(1, 1.0));
+        recipe.speed_algorithm = crate::types::SpeedAlgorithm::Resample;
+        recipe.silence_fade_ms = 4.0;
+
+        let results = apply_recipe(&chunk, &recipe, None);
+
+        assert_eq!(results.len(), 4);
+        let gap = &results[1].samples;
+        assert_eq!(&gap[..4], &[0.4, 0.3, 0.2, 0.1]);
+        assert_eq!(&gap[46..], &[0.1, 0.2, 0.3, 0.4]);
+        assert!(gap[4..46].iter().all(|&s| s == 0.0));
+        // The trailing silence fades out of the chunk but has nothing to fade into
+        let tail = &results[3].samples;
+        assert_eq!(tail[0], 0.4);
+        assert_eq!(*tail.last().unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_recipe_mix_beds_audio_repeats_only() {
+        let chunk = AudioChunk {
+            samples: vec![0.25; 100],
+            sample_rate: 1000,
+            start_time: 0.0,
+            end_time: 0.1,
+        };
+        let mut recipe = Recipe::new("shadowing")
+            .add_step(RecipeStep::audio(1, 1.0))
+            .add_step(RecipeStep::silence(1, 1.0));
+        recipe.speed_algorithm = crate::types::SpeedAlgorithm::Resample;
+        recipe.mix = Some(crate::types::Mix {
+            bed: crate::types::MixBed::Chunk,
+            gain_db: -20.0,
+        });
+
+        let results = apply_recipe(&chunk, &recipe, None);
+
+        assert!(results[0].samples.iter().all(|&s| (s - 0.275).abs() < 1e-6));
+        assert!(results[1].samples.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_recipe_on_empty_chunk_yields_empty_chunks() {
+        let chunk = AudioChunk {
+            samples: Vec::new(),
+            sample_rate: 44100,
+            start_time: 0.0,
+            end_time: 0.0,
+        };
+        let recipe = Recipe::new("empty-input")
+            .add_step(RecipeStep::audio(2, 0.5))
+            .add_step(RecipeStep::silence(1, 1.5));
+
+        let results = apply_recipe(&chunk, &recipe, None);
+
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|result| result.samples.is_empty()));
+    }
 }
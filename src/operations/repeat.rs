@@ -5,6 +5,7 @@
 //! - Returns Vec of cloned chunks
 //! - No side effects
 
+use super::insert_silence_with_floor;
 use crate::types::AudioChunk;
 
 /// Repeats an audio chunk N times
@@ -22,6 +23,36 @@ pub fn repeat_chunk(chunk: &AudioChunk, count: u32) -> Vec<AudioChunk> {
     (0..count).map(|_| chunk.clone()).collect()
 }
 
+/// Repeats an audio chunk N times with a silent gap between consecutive copies
+///
+/// # Arguments
+/// * `chunk` - The audio chunk to repeat
+/// * `count` - Number of times to repeat (0 returns empty vec)
+/// * `gap_ms` - Silence inserted between copies in milliseconds (0.0 behaves like `repeat_chunk`)
+/// * `silence_floor` - Comfort-noise RMS for the gaps (0.0 keeps them silent)
+///
+/// # Returns
+/// Vector of `count` clones interleaved with `count - 1` silence chunks
+pub fn repeat_chunk_with_gap(
+    chunk: &AudioChunk,
+    count: u32,
+    gap_ms: f64,
+    silence_floor: f32,
+) -> Vec<AudioChunk> {
+    if gap_ms <= 0.0 {
+        return repeat_chunk(chunk, count);
+    }
+    let gap = insert_silence_with_floor(gap_ms / 1000.0, chunk.sample_rate, silence_floor);
+    let mut results = Vec::with_capacity((count as usize * 2).saturating_sub(1));
+    for idx in 0..count {
+        if idx > 0 {
+            results.push(gap.clone());
+        }
+        results.push(chunk.clone());
+    }
+    results
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -62,4 +93,29 @@ mod tests {
             assert_eq!(repeated_chunk.sample_rate, chunk.sample_rate);
         }
     }
+
+    #[test]
+    fn test_repeat_with_gap_zero_matches_plain_repeat() {
+        let chunk = create_test_chunk();
+        let result = repeat_chunk_with_gap(&chunk, 3, 0.0, 0.0);
+        assert_eq!(result.len(), 3);
+    }
+
+    #[test]
+    fn test_repeat_with_gap_inserts_silence_between_copies() {
+        let chunk = AudioChunk {
+            samples: vec![0.5; 1000],
+            sample_rate: 10_000,
+            start_time: 0.0,
+            end_time: 0.1,
+        };
+        let result = repeat_chunk_with_gap(&chunk, 3, 100.0, 0.0);
+
+        assert_eq!(result.len(), 5);
+        let total: usize = result.iter().map(|c| c.samples.len()).sum();
+        assert_eq!(total, 1000 * 3 + 1000 * 2);
+        assert!(result[1].samples.iter().all(|&s| s == 0.0));
+        assert!(result[3].samples.iter().all(|&s| s == 0.0));
+        assert_eq!(result[4].samples, chunk.samples);
+    }
 }
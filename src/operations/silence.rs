@@ -36,6 +36,64 @@ pub fn insert_silence(duration: f64, sample_rate: u32) -> AudioChunk {
     }
 }
 
+/// Generates a silence chunk filled with low-level comfort noise
+///
+/// # Arguments
+/// * `duration` - Duration in seconds
+/// * `sample_rate` - Sample rate in Hz
+/// * `floor` - RMS amplitude of the noise (0.0 returns pure silence)
+///
+/// # Returns
+/// AudioChunk whose samples are deterministic white noise scaled to `floor` RMS
+pub fn insert_silence_with_floor(duration: f64, sample_rate: u32, floor: f32) -> AudioChunk {
+    let mut silence = insert_silence(duration, sample_rate);
+    if floor > 0.0 {
+        silence.samples = comfort_noise(silence.samples.len(), floor);
+    }
+    silence
+}
+
+/// Ramps a silence chunk's edges from and to the neighbouring audio's level
+///
+/// The first `fade_ms` decay linearly from `lead_in` (the last sample before the
+/// silence) towards zero, and the last `fade_ms` rise from zero towards `lead_out`
+/// (the first sample after it), so speech settles into and out of the gap instead of
+/// stepping. Each fade is capped at half the chunk; the interior is left untouched.
+///
+/// # Arguments
+/// * `silence` - The silence chunk to fade in place
+/// * `lead_in` - Sample value just before the silence
+/// * `lead_out` - Sample value just after the silence
+/// * `fade_ms` - Length of each edge ramp in milliseconds (0.0 leaves the chunk unchanged)
+pub fn fade_silence_edges(silence: &mut AudioChunk, lead_in: f32, lead_out: f32, fade_ms: f64) {
+    let len = silence.samples.len();
+    let fade = ((fade_ms / 1000.0 * silence.sample_rate as f64).round() as usize).min(len / 2);
+    let steps = (fade + 1) as f32;
+    for i in 0..fade {
+        silence.samples[i] += lead_in * (fade - i) as f32 / steps;
+        silence.samples[len - fade + i] += lead_out * (i + 1) as f32 / steps;
+    }
+}
+
+/// Xorshift white noise rescaled so its RMS is exactly `rms`
+fn comfort_noise(len: usize, rms: f32) -> Vec<f32> {
+    let mut state: u32 = 0x9E37_79B9;
+    let noise: Vec<f32> = (0..len)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state as f32 / u32::MAX as f32 * 2.0 - 1.0
+        })
+        .collect();
+    let energy = noise.iter().map(|&s| s * s).sum::<f32>() / len.max(1) as f32;
+    if energy == 0.0 {
+        return noise;
+    }
+    let scale = rms / energy.sqrt();
+    noise.into_iter().map(|s| s * scale).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -84,6 +142,44 @@ mod tests {
         assert!(silence.samples.iter().all(|&s| s == 0.0));
     }
 
+    #[test]
+    fn test_silence_floor_sets_rms() {
+        let silence = insert_silence_with_floor(0.5, 16000, 0.002);
+        assert_eq!(silence.samples.len(), 8000);
+        let rms = (silence.samples.iter().map(|&s| s * s).sum::<f32>()
+            / silence.samples.len() as f32)
+            .sqrt();
+        assert!((rms - 0.002).abs() < 1e-5);
+        assert!(silence.samples.iter().any(|&s| s != 0.0));
+    }
+
+    #[test]
+    fn test_silence_zero_floor_is_exact_zero() {
+        let silence = insert_silence_with_floor(0.5, 16000, 0.0);
+        assert!(silence.samples.iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_faded_silence_ramps_edges_only() {
+        let mut silence = insert_silence(0.1, 1000); // 100 samples
+        fade_silence_edges(&mut silence, 0.5, -0.4, 4.0);
+
+        assert_eq!(&silence.samples[..4], &[0.4, 0.3, 0.2, 0.1]);
+        let tail = &silence.samples[96..];
+        for (actual, expected) in tail.iter().zip([-0.08, -0.16, -0.24, -0.32]) {
+            assert!((actual - expected).abs() < 1e-6);
+        }
+        assert!(silence.samples[4..96].iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn test_silence_fade_longer_than_chunk_is_capped() {
+        let mut silence = insert_silence(0.004, 1000); // 4 samples
+        fade_silence_edges(&mut silence, 1.0, 1.0, 50.0);
+        assert_eq!(silence.samples.len(), 4);
+        assert!(silence.samples.iter().all(|&s| s > 0.0 && s < 1.0));
+    }
+
     #[test]
     fn test_silence_very_short() {
         let silence = insert_silence(0.001, 44100); // 1ms
@@ -4,23 +4,30 @@
 //! - Takes AudioChunk and speed_factor as input
 //! - Returns time-stretched AudioChunk
 //! - No side effects
-//! - Uses ssstretch (Signalsmith Stretch) for high-quality pitch-preserving time-stretch
+//! - Uses ssstretch (Signalsmith Stretch) for high-quality pitch-preserving time-stretch,
+//!   with WSOLA and plain resampling as faster `SpeedAlgorithm` alternatives
 
-use crate::types::AudioChunk;
+use super::wsola::wsola_stretch;
+use crate::audio::resample::resample_by_ratio;
+use crate::types::{
+    sanitize_samples, AudioChunk, SpeedAlgorithm, MAX_SPEED_FACTOR, MIN_SPEED_FACTOR,
+};
 use ssstretch::Stretch;
+use std::borrow::Cow;
 
-/// Changes the speed of an audio chunk without changing pitch
+/// Changes the speed of an audio chunk using the chosen `algorithm`
+///
+/// `Resample` is a plain rate change, so pitch shifts with speed. Factors outside
+/// `MIN_SPEED_FACTOR..=MAX_SPEED_FACTOR` are clamped so output size stays bounded.
 ///
 /// # Arguments
 /// * `chunk` - The audio chunk to time-stretch
 /// * `speed_factor` - Speed multiplier (< 1.0 = slower, 1.0 = unchanged, > 1.0 = faster)
-///
-/// # Returns
-/// AudioChunk with time-stretched audio
+/// * `algorithm` - How to stretch; see [`SpeedAlgorithm`]
 ///
 /// # Examples
 /// ```
-/// use flowalyzer::types::AudioChunk;
+/// use flowalyzer::types::{AudioChunk, SpeedAlgorithm};
 /// use flowalyzer::operations::speed::change_speed;
 ///
 /// let chunk = AudioChunk {
@@ -31,16 +38,33 @@ use ssstretch::Stretch;
 /// };
 ///
 /// // Make it 50% slower (2x longer)
-/// let slower = change_speed(&chunk, 0.5);
+/// let slower = change_speed(&chunk, 0.5, SpeedAlgorithm::Signalsmith);
 /// assert!(slower.samples.len() > chunk.samples.len());
 /// ```
-pub fn change_speed(chunk: &AudioChunk, speed_factor: f32) -> AudioChunk {
+pub fn change_speed(
+    chunk: &AudioChunk,
+    speed_factor: f32,
+    algorithm: SpeedAlgorithm,
+) -> AudioChunk {
+    let speed_factor = clamp_speed_factor(speed_factor);
     if is_identity_speed(speed_factor) {
         return chunk.clone();
     }
 
-    let mut stretch = configured_stretch(chunk.sample_rate);
-    let samples = collect_stretched_samples(&mut stretch, &chunk.samples, speed_factor);
+    let input = finite_samples(&chunk.samples);
+    let samples = match algorithm {
+        SpeedAlgorithm::Signalsmith => {
+            let mut stretch = configured_stretch(chunk.sample_rate);
+            collect_stretched_samples(&mut stretch, &input, speed_factor)
+        }
+        SpeedAlgorithm::Wsola => wsola_stretch(&input, chunk.sample_rate, speed_factor),
+        SpeedAlgorithm::Resample if input.is_empty() => Vec::new(),
+        SpeedAlgorithm::Resample => resample_by_ratio(
+            &input,
+            speed_factor as f64,
+            compute_output_len(input.len(), speed_factor),
+        ),
+    };
     let new_duration = samples.len() as f64 / chunk.sample_rate as f64;
 
     AudioChunk {
@@ -51,6 +75,25 @@ pub fn change_speed(chunk: &AudioChunk, speed_factor: f32) -> AudioChunk {
     }
 }
 
+/// Non-finite samples would poison the stretcher state, so zero them first
+fn finite_samples(samples: &[f32]) -> Cow<'_, [f32]> {
+    if samples.iter().all(|sample| sample.is_finite()) {
+        return Cow::Borrowed(samples);
+    }
+    let mut cleaned = samples.to_vec();
+    sanitize_samples(&mut cleaned);
+    Cow::Owned(cleaned)
+}
+
+/// Keep `speed_factor` within the supported range; non-finite factors leave speed unchanged
+fn clamp_speed_factor(speed_factor: f32) -> f32 {
+    if speed_factor.is_finite() {
+        speed_factor.clamp(MIN_SPEED_FACTOR, MAX_SPEED_FACTOR)
+    } else {
+        1.0
+    }
+}
+
 fn is_identity_speed(speed_factor: f32) -> bool {
     (speed_factor - 1.0).abs() < 1e-6
 }
@@ -154,7 +197,7 @@ mod tests {
     #[test]
     fn identity_speed_returns_original_chunk() {
         let chunk = create_test_chunk(1024);
-        let result = change_speed(&chunk, 1.0);
+        let result = change_speed(&chunk, 1.0, SpeedAlgorithm::Signalsmith);
 
         assert_eq!(result.sample_rate, chunk.sample_rate);
         assert_eq!(result.samples, chunk.samples);
@@ -165,8 +208,8 @@ mod tests {
         let chunk = create_test_chunk(2048);
         let slow_factor = 0.75;
 
-        let slowed = change_speed(&chunk, slow_factor);
-        let back = change_speed(&slowed, 1.0 / slow_factor);
+        let slowed = change_speed(&chunk, slow_factor, SpeedAlgorithm::Signalsmith);
+        let back = change_speed(&slowed, 1.0 / slow_factor, SpeedAlgorithm::Signalsmith);
 
         assert_eq!(back.sample_rate, chunk.sample_rate);
         assert!(
@@ -180,8 +223,8 @@ mod tests {
         let chunk = create_test_chunk(2048);
         let fast_factor = 1.8;
 
-        let sped = change_speed(&chunk, fast_factor);
-        let back = change_speed(&sped, 1.0 / fast_factor);
+        let sped = change_speed(&chunk, fast_factor, SpeedAlgorithm::Signalsmith);
+        let back = change_speed(&sped, 1.0 / fast_factor, SpeedAlgorithm::Signalsmith);
 
         assert_eq!(back.sample_rate, chunk.sample_rate);
         assert!(
@@ -189,4 +232,52 @@ mod tests {
             "fast → normal round-trip should not clip samples"
         );
     }
+
+    #[test]
+    fn each_algorithm_produces_expected_length() {
+        let chunk = create_test_chunk(44_100);
+        for factor in [0.5, 1.5] {
+            let expected = (44_100.0 / factor as f64).round() as usize;
+            let signalsmith = change_speed(&chunk, factor, SpeedAlgorithm::Signalsmith);
+            let wsola = change_speed(&chunk, factor, SpeedAlgorithm::Wsola);
+            let resampled = change_speed(&chunk, factor, SpeedAlgorithm::Resample);
+
+            assert!(signalsmith.samples.len() >= expected);
+            assert_eq!(wsola.samples.len(), expected);
+            assert_eq!(resampled.samples.len(), expected);
+            assert!(wsola.samples.iter().all(|sample| sample.is_finite()));
+        }
+    }
+
+    #[test]
+    fn extreme_factors_are_clamped_to_supported_range() {
+        let chunk = create_test_chunk(1_000);
+
+        let crawl = change_speed(&chunk, 0.001, SpeedAlgorithm::Resample);
+        let sprint = change_speed(&chunk, 1_000.0, SpeedAlgorithm::Resample);
+
+        assert_eq!(crawl.samples.len(), 10_000);
+        assert_eq!(sprint.samples.len(), 100);
+        assert_eq!(
+            change_speed(&chunk, f32::NAN, SpeedAlgorithm::Signalsmith).samples,
+            chunk.samples
+        );
+    }
+
+    #[test]
+    fn empty_chunk_returns_empty_output() {
+        let chunk = create_test_chunk(0);
+        let result = change_speed(&chunk, 0.5, SpeedAlgorithm::Signalsmith);
+        assert!(result.samples.is_empty());
+    }
+
+    #[test]
+    fn non_finite_input_does_not_reach_output() {
+        let mut chunk = create_test_chunk(2048);
+        chunk.samples[10] = f32::NAN;
+        chunk.samples[20] = f32::INFINITY;
+
+        let result = change_speed(&chunk, 0.75, SpeedAlgorithm::Signalsmith);
+        assert!(result.samples.iter().all(|sample| sample.is_finite()));
+    }
 }
@@ -0,0 +1,94 @@
+//! WSOLA time-stretch - waveform-similarity overlap-add
+//!
+//! A cheaper pitch-preserving alternative to Signalsmith Stretch: Hann-windowed
+//! frames are copied from the input at the stretched position, each nudged
+//! within a small tolerance to line up with the previous frame's natural
+//! continuation, then overlap-added at a fixed output hop.
+
+/// Analysis frame length in seconds
+const FRAME_SECONDS: f64 = 0.03;
+
+/// Stride used when scoring candidate offsets; coarse matching is plenty for alignment
+const CORRELATION_STRIDE: usize = 4;
+
+/// Stretch `samples` by `1 / speed_factor`, producing `round(len / speed_factor)` samples
+pub fn wsola_stretch(samples: &[f32], sample_rate: u32, speed_factor: f32) -> Vec<f32> {
+    let target_len = ((samples.len() as f64) / speed_factor as f64).round() as usize;
+    let frame = ((sample_rate as f64 * FRAME_SECONDS) as usize).max(4);
+    if samples.len() < frame {
+        return crate::audio::resample::resample_by_ratio(samples, speed_factor as f64, target_len);
+    }
+    let hop = frame / 2;
+    let tolerance = frame / 8;
+    let window = hann(frame);
+
+    let mut output = vec![0.0f32; target_len + frame];
+    let mut weights = vec![0.0f32; target_len + frame];
+    let mut previous: Option<usize> = None;
+    let last_start = samples.len() - frame;
+    let mut out_pos = 0;
+    while out_pos < target_len {
+        let nominal = ((out_pos as f64 * speed_factor as f64) as usize).min(last_start);
+        let in_pos = match previous {
+            Some(prev) => best_offset(
+                samples,
+                nominal,
+                (prev + hop).min(last_start),
+                frame,
+                tolerance,
+            ),
+            None => nominal,
+        };
+        for (i, &w) in window.iter().enumerate() {
+            output[out_pos + i] += samples[in_pos + i] * w;
+            weights[out_pos + i] += w;
+        }
+        previous = Some(in_pos);
+        out_pos += hop;
+    }
+
+    output.truncate(target_len);
+    for (sample, &weight) in output.iter_mut().zip(&weights) {
+        if weight > 1e-3 {
+            *sample /= weight;
+        }
+    }
+    output
+}
+
+/// Start near `nominal` whose frame best matches the frame at `continuation`
+fn best_offset(
+    samples: &[f32],
+    nominal: usize,
+    continuation: usize,
+    frame: usize,
+    tolerance: usize,
+) -> usize {
+    let last_start = samples.len() - frame;
+    let low = nominal.saturating_sub(tolerance);
+    let high = (nominal + tolerance).min(last_start);
+    let reference = &samples[continuation..continuation + frame];
+    (low..=high)
+        .max_by(|&a, &b| {
+            correlation(reference, &samples[a..a + frame])
+                .total_cmp(&correlation(reference, &samples[b..b + frame]))
+        })
+        .unwrap_or(nominal)
+}
+
+fn correlation(a: &[f32], b: &[f32]) -> f32 {
+    a.iter()
+        .zip(b)
+        .step_by(CORRELATION_STRIDE)
+        .map(|(x, y)| x * y)
+        .sum()
+}
+
+fn hann(len: usize) -> Vec<f32> {
+    (0..len)
+        .map(|i| {
+            let phase = 2.0 * std::f32::consts::PI * i as f32 / len as f32;
+            0.5 - 0.5 * phase.cos()
+        })
+        .collect()
+}
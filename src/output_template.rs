@@ -0,0 +1,167 @@
+//! Output path templates - expand `{stem}` and `{n}` placeholders per chunk
+//!
+//! `{stem}` is the source file's basename without extension and `{n}` is the
+//! 1-based chunk number, zero-padded to the run's pad width or explicitly as
+//! `{n:04}`. Templates without `{n}` get a `chunk_{n}` directory appended so
+//! chunks never collide.
+
+use anyhow::{bail, ensure, Result};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Stem,
+    /// `None` defers to the pad width passed to `resolve`
+    Index {
+        width: Option<usize>,
+    },
+}
+
+/// Parsed output directory template
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputTemplate {
+    parts: Vec<Part>,
+}
+
+impl OutputTemplate {
+    /// Parse a template, rejecting unknown or unterminated placeholders
+    pub fn parse(raw: &str) -> Result<Self> {
+        let mut parts = Vec::new();
+        let mut rest = raw;
+        while let Some(open) = rest.find('{') {
+            push_literal(&mut parts, &rest[..open]);
+            let Some(close) = rest[open..].find('}') else {
+                bail!("Unterminated placeholder in output template: {}", raw);
+            };
+            parts.push(parse_placeholder(&rest[open + 1..open + close])?);
+            rest = &rest[open + close + 1..];
+        }
+        push_literal(&mut parts, rest);
+        Ok(Self { parts })
+    }
+
+    /// Build the directory for chunk `n` (1-based) of the source named `stem`,
+    /// padding `{n}` to `pad_width` digits unless the template sets its own width
+    pub fn resolve(&self, stem: &str, n: usize, pad_width: usize) -> PathBuf {
+        let mut path = String::new();
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => path.push_str(text),
+                Part::Stem => path.push_str(stem),
+                Part::Index { width } => {
+                    let width = width.unwrap_or(pad_width);
+                    path.push_str(&format!("{:0width$}", n))
+                }
+            }
+        }
+        let path = PathBuf::from(path);
+        if self.has_index() {
+            path
+        } else {
+            path.join(format!("chunk_{:0width$}", n, width = pad_width))
+        }
+    }
+
+    /// Whether the template already separates sources by `{stem}`
+    pub fn has_stem(&self) -> bool {
+        self.parts.contains(&Part::Stem)
+    }
+
+    fn has_index(&self) -> bool {
+        self.parts
+            .iter()
+            .any(|part| matches!(part, Part::Index { .. }))
+    }
+}
+
+/// Digits needed to number `total` chunks, so names sort correctly without excess zeros
+pub fn auto_pad_width(total: usize) -> usize {
+    total.max(1).to_string().len()
+}
+
+fn push_literal(parts: &mut Vec<Part>, text: &str) {
+    if !text.is_empty() {
+        parts.push(Part::Literal(text.to_string()));
+    }
+}
+
+fn parse_placeholder(spec: &str) -> Result<Part> {
+    let (name, format) = match spec.split_once(':') {
+        Some((name, format)) => (name, Some(format)),
+        None => (spec, None),
+    };
+    match (name, format) {
+        ("stem", None) => Ok(Part::Stem),
+        ("n", None) => Ok(Part::Index { width: None }),
+        ("n", Some(width)) => parse_width(width),
+        ("stem", Some(_)) => bail!("Padding is only supported for {{n}}, got {{{}}}", spec),
+        _ => bail!("Unknown output template placeholder: {{{}}}", spec),
+    }
+}
+
+fn parse_width(format: &str) -> Result<Part> {
+    ensure!(
+        !format.is_empty() && format.chars().all(|c| c.is_ascii_digit()),
+        "Invalid padding for {{n}}: {:?} (expected digits, e.g. {{n:04}})",
+        format
+    );
+    Ok(Part::Index {
+        width: Some(format.parse()?),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_stem_and_padded_index() {
+        let template = OutputTemplate::parse("out/{stem}/chunk_{n:04}").unwrap();
+        assert_eq!(
+            template.resolve("lesson1", 7, 2),
+            PathBuf::from("out/lesson1/chunk_0007")
+        );
+    }
+
+    #[test]
+    fn bare_index_uses_pad_width() {
+        let template = OutputTemplate::parse("out/{stem}-{n}").unwrap();
+        assert_eq!(
+            template.resolve("talk", 12, 2),
+            PathBuf::from("out/talk-12")
+        );
+        assert_eq!(
+            template.resolve("talk", 3, 3),
+            PathBuf::from("out/talk-003")
+        );
+    }
+
+    #[test]
+    fn auto_pad_width_matches_chunk_count_digits() {
+        assert_eq!(auto_pad_width(0), 1);
+        assert_eq!(auto_pad_width(9), 1);
+        assert_eq!(auto_pad_width(12), 2);
+        assert_eq!(auto_pad_width(10_000), 5);
+    }
+
+    #[test]
+    fn missing_index_appends_default_chunk_dir() {
+        let plain = OutputTemplate::parse("out").unwrap();
+        assert_eq!(plain.resolve("talk", 3, 4), PathBuf::from("out/chunk_0003"));
+
+        let with_stem = OutputTemplate::parse("out/{stem}").unwrap();
+        assert_eq!(
+            with_stem.resolve("talk", 3, 2),
+            PathBuf::from("out/talk/chunk_03")
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_and_malformed_placeholders() {
+        assert!(OutputTemplate::parse("out/{name}").is_err());
+        assert!(OutputTemplate::parse("out/{n").is_err());
+        assert!(OutputTemplate::parse("out/{n:xx}").is_err());
+        assert!(OutputTemplate::parse("out/{stem:04}").is_err());
+    }
+}
@@ -0,0 +1,197 @@
+//! Single-file output - join every processed chunk into one practice track
+//!
+//! Chunks are appended in order with a fixed gap between them. Gaps are plain
+//! silence (or comfort noise), so chunks are butted against them rather than
+//! crossfaded and the track length is exactly the chunks plus the gaps.
+//!
+//! Chapter markers are written as an FFMETADATA file (no 99-track limit, unlike
+//! CUE), which `ffmpeg -i track.wav -i track.ffmetadata -map_metadata 1` muxes in.
+
+use crate::operations::insert_silence_with_floor;
+use crate::types::AudioData;
+use anyhow::{ensure, Context, Result};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+/// Where a chunk begins within the joined track
+#[derive(Debug, Clone, PartialEq)]
+pub struct CuePoint {
+    pub start_seconds: f64,
+    pub title: String,
+}
+
+/// Accumulates processed chunks into one continuous track
+#[derive(Debug)]
+pub struct SingleTrack {
+    samples: Vec<f32>,
+    sample_rate: Option<u32>,
+    gap_seconds: f64,
+    silence_floor: f32,
+    cues: Vec<CuePoint>,
+}
+
+impl SingleTrack {
+    /// Start an empty track that separates chunks by `gap_seconds`
+    pub fn new(gap_seconds: f64, silence_floor: f32) -> Self {
+        Self {
+            samples: Vec::new(),
+            sample_rate: None,
+            gap_seconds,
+            silence_floor,
+            cues: Vec::new(),
+        }
+    }
+
+    /// Append a chunk, preceded by the gap unless it is the first
+    pub fn push(&mut self, audio: &AudioData, title: String) -> Result<()> {
+        let sample_rate = *self.sample_rate.get_or_insert(audio.sample_rate);
+        ensure!(
+            audio.sample_rate == sample_rate,
+            "Chunk sample rate {} Hz does not match the track's {} Hz",
+            audio.sample_rate,
+            sample_rate
+        );
+        if !self.cues.is_empty() && self.gap_seconds > 0.0 {
+            let gap = insert_silence_with_floor(self.gap_seconds, sample_rate, self.silence_floor);
+            self.samples.extend(gap.samples);
+        }
+        self.cues.push(CuePoint {
+            start_seconds: self.samples.len() as f64 / sample_rate as f64,
+            title,
+        });
+        self.samples.extend_from_slice(&audio.samples);
+        Ok(())
+    }
+
+    /// The joined audio and a cue per chunk, or `None` if nothing was pushed
+    pub fn finish(self) -> Option<(AudioData, Vec<CuePoint>)> {
+        let sample_rate = self.sample_rate?;
+        let audio = AudioData {
+            samples: self.samples,
+            sample_rate,
+        };
+        Some((audio, self.cues))
+    }
+}
+
+/// Write one chapter per cue, each ending where the next begins (the last at `total_seconds`)
+pub fn write_cue_sheet(cues: &[CuePoint], total_seconds: f64, path: &Path) -> Result<()> {
+    let mut sheet = String::from(";FFMETADATA1\n");
+    for (idx, cue) in cues.iter().enumerate() {
+        let end = cues
+            .get(idx + 1)
+            .map_or(total_seconds, |next| next.start_seconds);
+        let title = match cue.title.trim() {
+            "" => format!("Chunk {}", idx + 1),
+            title => title.to_string(),
+        };
+        let _ = write!(
+            sheet,
+            "\n[CHAPTER]\nTIMEBASE=1/1000\nSTART={}\nEND={}\ntitle={}\n",
+            to_millis(cue.start_seconds),
+            to_millis(end),
+            escape_value(&title)
+        );
+    }
+    fs::write(path, sheet).with_context(|| format!("Failed to write cue sheet {:?}", path))
+}
+
+fn to_millis(seconds: f64) -> u64 {
+    (seconds * 1000.0).round() as u64
+}
+
+/// FFMETADATA reserves `=`, `;`, `#`, `\` and newlines; each is escaped with a backslash
+fn escape_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        if c != '\r' {
+            escaped.push(c);
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn audio(len: usize) -> AudioData {
+        AudioData {
+            samples: vec![0.5; len],
+            sample_rate: 1_000,
+        }
+    }
+
+    #[test]
+    fn track_length_is_chunks_plus_gaps() {
+        let mut track = SingleTrack::new(0.25, 0.0);
+        for (idx, len) in [400, 700, 300].into_iter().enumerate() {
+            track
+                .push(&audio(len), format!("chunk {}", idx + 1))
+                .unwrap();
+        }
+
+        let (joined, cues) = track.finish().unwrap();
+
+        assert_eq!(joined.samples.len(), 400 + 700 + 300 + 2 * 250);
+        assert_eq!(cues.len(), 3);
+        let starts: Vec<f64> = cues.iter().map(|cue| cue.start_seconds).collect();
+        assert_eq!(starts, vec![0.0, 0.65, 1.6]);
+        assert!(joined.samples[400..650].iter().all(|&s| s == 0.0));
+    }
+
+    #[test]
+    fn cue_sheet_round_trips_chapter_offsets() {
+        let cues = vec![
+            CuePoint {
+                start_seconds: 0.0,
+                title: "Hola; ¿qué tal?".to_string(),
+            },
+            CuePoint {
+                start_seconds: 1.25,
+                title: "a=b #1".to_string(),
+            },
+            CuePoint {
+                start_seconds: 3.5,
+                title: String::new(),
+            },
+        ];
+        let path =
+            std::env::temp_dir().join(format!("flowalyzer_cues_{}.ffmetadata", std::process::id()));
+
+        write_cue_sheet(&cues, 5.0, &path).unwrap();
+        let sheet = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let field = |key: &str| -> Vec<String> {
+            sheet
+                .lines()
+                .filter_map(|line| line.strip_prefix(key))
+                .map(str::to_string)
+                .collect()
+        };
+        assert!(sheet.starts_with(";FFMETADATA1\n"));
+        assert_eq!(field("START="), vec!["0", "1250", "3500"]);
+        assert_eq!(field("END="), vec!["1250", "3500", "5000"]);
+        assert_eq!(
+            field("title="),
+            vec!["Hola\\; ¿qué tal?", "a\\=b \\#1", "Chunk 3"]
+        );
+    }
+
+    #[test]
+    fn rejects_mismatched_sample_rates_and_empty_tracks() {
+        let mut track = SingleTrack::new(0.0, 0.0);
+        track.push(&audio(10), String::new()).unwrap();
+        let other = AudioData {
+            samples: vec![0.0; 10],
+            sample_rate: 2_000,
+        };
+        assert!(track.push(&other, String::new()).is_err());
+        assert!(SingleTrack::new(0.0, 0.0).finish().is_none());
+    }
+}
@@ -3,17 +3,106 @@
 //! Uses whisper-rs to transcribe audio and extract word-level timing data.
 //! This enables linguistic boundary detection for intelligent chunking.
 
+use crate::audio::resample::resample;
+use crate::cancel::{self, CancelFlag};
 use crate::types::{AudioData, Granularity, Segment, Transcript};
 use anyhow::{Context, Result};
+use std::borrow::Cow;
+use std::ops::Range;
 use std::path::Path;
+use std::sync::Arc;
 use whisper_rs::{FullParams, SamplingStrategy, WhisperContext, WhisperContextParameters};
 
+/// Sample rate Whisper models are trained on
+pub const TARGET_SAMPLE_RATE: u32 = 16_000;
+
+/// Default segment durations, in seconds, classified as phrases
+pub const DEFAULT_PHRASE_BAND: Range<f64> = 0.6..2.0;
+
+/// Whisper decoding strategy
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Decoding {
+    /// Sample `best_of` candidates per window and keep the most likely
+    Greedy { best_of: usize },
+    /// Beam search; `patience` scales how long beams keep searching after a candidate ends
+    BeamSearch { beam_size: usize, patience: f32 },
+}
+
+impl Decoding {
+    fn strategy(self) -> SamplingStrategy {
+        match self {
+            Decoding::Greedy { best_of } => SamplingStrategy::Greedy {
+                best_of: best_of as i32,
+            },
+            Decoding::BeamSearch {
+                beam_size,
+                patience,
+            } => SamplingStrategy::BeamSearch {
+                beam_size: beam_size as i32,
+                patience,
+            },
+        }
+    }
+}
+
+/// Decoding used when the model size cannot be read from its file name
+const FALLBACK_DECODING: Decoding = Decoding::Greedy { best_of: 1 };
+
+/// Default decoding for a model, keyed on the size in its file name (e.g. `ggml-small.en.bin`).
+///
+/// Tiny and base models drift without a search, so they get beam search; small models
+/// gain from a few greedy candidates; medium and large models are accurate enough
+/// that a single greedy pass is the best trade-off.
+fn model_decoding(path: &str) -> Decoding {
+    let name = Path::new(path)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+    if name.contains("tiny") || name.contains("base") {
+        Decoding::BeamSearch {
+            beam_size: 5,
+            patience: 1.0,
+        }
+    } else if name.contains("small") {
+        Decoding::Greedy { best_of: 5 }
+    } else if name.contains("medium") || name.contains("large") {
+        Decoding::Greedy { best_of: 1 }
+    } else {
+        FALLBACK_DECODING
+    }
+}
+
 /// Configuration for a transcription run
 #[derive(Debug, Clone)]
 pub struct TranscriptionSettings {
     pub model_path: String,
     pub language: Option<String>,
     pub detect_language: bool,
+    /// Rate audio is resampled to before being handed to Whisper
+    pub sample_rate: u32,
+    /// Number of CPU threads Whisper decodes with
+    pub threads: usize,
+    /// Text fed to Whisper as prior context to bias vocabulary and spelling
+    pub initial_prompt: Option<String>,
+    /// Initial sampling temperature; `None` keeps Whisper's default
+    pub temperature: Option<f32>,
+    /// Probability above which a window is treated as no speech; `None` keeps Whisper's default
+    pub no_speech_threshold: Option<f32>,
+    /// Entropy above which decoding falls back to a higher temperature; `None` keeps Whisper's default
+    pub entropy_threshold: Option<f32>,
+    /// Segment durations (seconds) classified as phrases; shorter are words, longer sentences
+    pub phrase_band: Range<f64>,
+    /// Fold a segment overlapping its predecessor into it instead of trimming its start
+    pub merge_overlaps: bool,
+    /// Decoding picked for the model by `apply_model_defaults`
+    pub model_decoding: Decoding,
+    /// Explicit beam width; switches to beam search regardless of the model default
+    pub beam_size: Option<usize>,
+    /// Explicit greedy candidate count, used when no beam width is set
+    pub best_of: Option<usize>,
+    /// Explicit beam-search patience
+    pub patience: Option<f32>,
 }
 
 impl Default for TranscriptionSettings {
@@ -24,18 +113,56 @@ impl Default for TranscriptionSettings {
             model_path,
             language: None,
             detect_language: true,
+            sample_rate: TARGET_SAMPLE_RATE,
+            threads: default_thread_count(),
+            initial_prompt: None,
+            temperature: None,
+            no_speech_threshold: None,
+            entropy_threshold: None,
+            phrase_band: DEFAULT_PHRASE_BAND,
+            merge_overlaps: false,
+            model_decoding: FALLBACK_DECODING,
+            beam_size: None,
+            best_of: None,
+            patience: None,
         };
         settings.apply_model_defaults();
         settings
     }
 }
 
+fn default_thread_count() -> usize {
+    std::thread::available_parallelism()
+        .map(|count| count.get())
+        .unwrap_or(1)
+}
+
 impl TranscriptionSettings {
     pub fn apply_model_defaults(&mut self) {
         if self.language.is_none() && self.detect_language && self.is_english_only_model() {
             self.language = Some("en".to_string());
             self.detect_language = false;
         }
+        self.model_decoding = model_decoding(&self.model_path);
+    }
+
+    /// Decoding to run: explicit beam size or best-of settings win over the model default
+    pub fn decoding(&self) -> Decoding {
+        let decoding = match (self.beam_size, self.best_of) {
+            (Some(beam_size), _) => Decoding::BeamSearch {
+                beam_size,
+                patience: 1.0,
+            },
+            (None, Some(best_of)) => Decoding::Greedy { best_of },
+            (None, None) => self.model_decoding,
+        };
+        match (decoding, self.patience) {
+            (Decoding::BeamSearch { beam_size, .. }, Some(patience)) => Decoding::BeamSearch {
+                beam_size,
+                patience,
+            },
+            _ => decoding,
+        }
     }
 
     pub fn is_english_only_model(&self) -> bool {
@@ -58,29 +185,41 @@ impl TranscriptionSettings {
 ///
 /// # Returns
 /// Transcript with segments containing text and timing information
-pub fn transcribe_audio(audio: &AudioData, settings: &TranscriptionSettings) -> Result<Transcript> {
+pub fn transcribe_audio(
+    audio: &AudioData,
+    settings: &TranscriptionSettings,
+    cancel: &CancelFlag,
+) -> Result<Transcript> {
     let ctx = WhisperContext::new_with_params(
         &settings.model_path,
         WhisperContextParameters::default(),
     ).context("Failed to load Whisper model. Download with: wget https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin -P ./models/")?;
 
-    let mut params = FullParams::new(SamplingStrategy::Greedy { best_of: 1 });
+    let mut params = FullParams::new(settings.decoding().strategy());
     params.set_print_special(false);
     params.set_print_progress(false);
     params.set_print_realtime(false);
     params.set_print_timestamps(false);
     params.set_translate(false);
+    params.set_n_threads(settings.threads as i32);
+    apply_decoding_thresholds(&mut params, settings);
+    if let Some(prompt) = settings.initial_prompt.as_deref() {
+        params.set_initial_prompt(prompt);
+    }
     match settings.language.as_deref() {
         Some(language) => params.set_language(Some(language)),
         None => params.set_language(None),
     }
+    let abort = Arc::clone(cancel);
+    params.set_abort_callback_safe(move || cancel::is_cancelled(&abort));
 
     let mut state = ctx
         .create_state()
         .context("Failed to create Whisper state")?;
-    state
-        .full(params, &audio.samples)
-        .context("Failed to transcribe audio")?;
+    let input = whisper_input(audio, settings.sample_rate);
+    let result = state.full(params, &input.samples);
+    cancel::check(cancel)?;
+    result.context("Failed to transcribe audio")?;
 
     // Extract segments with timing using iterator
     let mut segments = Vec::new();
@@ -94,12 +233,7 @@ pub fn transcribe_audio(audio: &AudioData, settings: &TranscriptionSettings) ->
         // Timestamps are in centiseconds (10s of milliseconds), convert to seconds
         let start_time = segment.start_timestamp() as f64 / 100.0;
         let end_time = segment.end_timestamp() as f64 / 100.0;
-        let duration = end_time - start_time;
-        let granularity = if duration < 1.0 {
-            Granularity::Word
-        } else {
-            Granularity::Sentence
-        };
+        let granularity = classify_granularity(end_time - start_time, &settings.phrase_band);
 
         segments.push(Segment {
             text,
@@ -109,13 +243,310 @@ pub fn transcribe_audio(audio: &AudioData, settings: &TranscriptionSettings) ->
         });
     }
 
+    let repaired = sanitize_segments(
+        &mut segments,
+        settings.merge_overlaps,
+        &settings.phrase_band,
+    );
+    if repaired > 0 {
+        eprintln!(
+            "   Warning: repaired {} out-of-order Whisper segment timestamps",
+            repaired
+        );
+    }
+
     Ok(Transcript { segments })
 }
 
+/// Make segment timing monotonic so every segment slices to a forward, non-overlapping range
+///
+/// Ends before starts are clamped to the start, segments are sorted by start, and a
+/// segment overlapping its predecessor is trimmed to begin where that one ends or, with
+/// `merge_overlaps`, folded into it. Granularity is reclassified against `phrase_band`
+/// afterwards. Returns the number of repairs made.
+pub fn sanitize_segments(
+    segments: &mut Vec<Segment>,
+    merge_overlaps: bool,
+    phrase_band: &Range<f64>,
+) -> usize {
+    let mut repairs = segments
+        .windows(2)
+        .filter(|pair| pair[1].start_time < pair[0].start_time)
+        .count();
+    for segment in segments.iter_mut() {
+        if segment.end_time < segment.start_time {
+            segment.end_time = segment.start_time;
+            repairs += 1;
+        }
+    }
+    segments.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+
+    let mut monotonic: Vec<Segment> = Vec::with_capacity(segments.len());
+    for mut segment in segments.drain(..) {
+        if let Some(previous) = monotonic.last_mut() {
+            if segment.start_time < previous.end_time {
+                repairs += 1;
+                if merge_overlaps {
+                    previous.text =
+                        format!("{} {}", previous.text.trim_end(), segment.text.trim_start());
+                    previous.end_time = previous.end_time.max(segment.end_time);
+                    continue;
+                }
+                segment.start_time = previous.end_time;
+                segment.end_time = segment.end_time.max(segment.start_time);
+            }
+        }
+        monotonic.push(segment);
+    }
+    for segment in &mut monotonic {
+        segment.granularity =
+            classify_granularity(segment.end_time - segment.start_time, phrase_band);
+    }
+    *segments = monotonic;
+    repairs
+}
+
+/// Word below `phrase_band`, phrase within it, sentence above it
+pub fn classify_granularity(duration: f64, phrase_band: &Range<f64>) -> Granularity {
+    if duration < phrase_band.start {
+        Granularity::Word
+    } else if duration < phrase_band.end {
+        Granularity::Phrase
+    } else {
+        Granularity::Sentence
+    }
+}
+
+/// Whisper parameters that guard against looping and hallucination on silence
+trait DecodingParams {
+    fn set_temperature(&mut self, value: f32);
+    fn set_no_speech_thold(&mut self, value: f32);
+    fn set_entropy_thold(&mut self, value: f32);
+}
+
+impl DecodingParams for FullParams<'_, '_> {
+    fn set_temperature(&mut self, value: f32) {
+        FullParams::set_temperature(self, value);
+    }
+
+    fn set_no_speech_thold(&mut self, value: f32) {
+        FullParams::set_no_speech_thold(self, value);
+    }
+
+    fn set_entropy_thold(&mut self, value: f32) {
+        FullParams::set_entropy_thold(self, value);
+    }
+}
+
+/// Apply only the thresholds that were configured, leaving Whisper defaults otherwise
+fn apply_decoding_thresholds(params: &mut impl DecodingParams, settings: &TranscriptionSettings) {
+    if let Some(temperature) = settings.temperature {
+        params.set_temperature(temperature);
+    }
+    if let Some(threshold) = settings.no_speech_threshold {
+        params.set_no_speech_thold(threshold);
+    }
+    if let Some(threshold) = settings.entropy_threshold {
+        params.set_entropy_thold(threshold);
+    }
+}
+
+/// Resample to `sample_rate` for Whisper. Timestamps are only in input seconds at
+/// `TARGET_SAMPLE_RATE`, the rate whisper.cpp assumes for every buffer
+pub fn whisper_input(audio: &AudioData, sample_rate: u32) -> Cow<'_, AudioData> {
+    if audio.sample_rate == sample_rate {
+        Cow::Borrowed(audio)
+    } else {
+        Cow::Owned(resample(audio, sample_rate))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[derive(Default)]
+    struct RecordedParams {
+        temperature: Option<f32>,
+        no_speech: Option<f32>,
+        entropy: Option<f32>,
+    }
+
+    impl DecodingParams for RecordedParams {
+        fn set_temperature(&mut self, value: f32) {
+            self.temperature = Some(value);
+        }
+
+        fn set_no_speech_thold(&mut self, value: f32) {
+            self.no_speech = Some(value);
+        }
+
+        fn set_entropy_thold(&mut self, value: f32) {
+            self.entropy = Some(value);
+        }
+    }
+
+    #[test]
+    fn segment_inside_phrase_band_is_a_phrase() {
+        let band = 0.6..2.0;
+        assert_eq!(classify_granularity(0.3, &band), Granularity::Word);
+        assert_eq!(classify_granularity(0.9, &band), Granularity::Phrase);
+        assert_eq!(classify_granularity(2.0, &band), Granularity::Sentence);
+    }
+
+    fn segment(text: &str, start_time: f64, end_time: f64) -> Segment {
+        Segment {
+            text: text.to_string(),
+            start_time,
+            end_time,
+            granularity: Granularity::Word,
+        }
+    }
+
+    fn assert_monotonic(segments: &[Segment]) {
+        for segment in segments {
+            assert!(segment.end_time >= segment.start_time);
+        }
+        for pair in segments.windows(2) {
+            assert!(pair[1].start_time >= pair[0].end_time);
+        }
+    }
+
+    #[test]
+    fn sanitize_fixes_reversed_and_overlapping_segments() {
+        let raw = vec![
+            segment(" one", 0.0, 1.0),
+            segment(" two", 2.0, 1.5),
+            segment(" three", 0.8, 1.8),
+        ];
+
+        let mut trimmed = raw.clone();
+        let repairs = sanitize_segments(&mut trimmed, false, &DEFAULT_PHRASE_BAND);
+        assert_eq!(repairs, 3); // reversed end, out of order, overlap
+        assert_monotonic(&trimmed);
+        assert_eq!(trimmed.len(), 3);
+        assert_eq!(trimmed[1].text, " three");
+        assert!((trimmed[1].start_time - 1.0).abs() < 1e-9);
+        assert_eq!(trimmed[1].granularity, Granularity::Phrase);
+
+        let mut merged = raw;
+        sanitize_segments(&mut merged, true, &DEFAULT_PHRASE_BAND);
+        assert_monotonic(&merged);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0].text, " one three");
+        assert!((merged[0].end_time - 1.8).abs() < 1e-9);
+
+        let boundaries = crate::chunking::calculate_chunk_boundaries(
+            &Transcript { segments: merged },
+            crate::types::ChunkConfig::new(2.0),
+            &[],
+        );
+        assert!(boundaries.iter().all(|b| b.end_time >= b.start_time));
+    }
+
+    #[test]
+    fn sanitize_leaves_monotonic_segments_alone() {
+        let mut segments = vec![segment("a", 0.0, 0.5), segment("b", 0.5, 1.0)];
+        assert_eq!(
+            sanitize_segments(&mut segments, true, &DEFAULT_PHRASE_BAND),
+            0
+        );
+        assert_eq!(segments.len(), 2);
+    }
+
+    fn settings_for(model_path: &str) -> TranscriptionSettings {
+        let mut settings = TranscriptionSettings {
+            model_path: model_path.to_string(),
+            ..TranscriptionSettings::default()
+        };
+        settings.apply_model_defaults();
+        settings
+    }
+
+    #[test]
+    fn model_size_picks_decoding_defaults() {
+        assert_eq!(
+            settings_for("models/ggml-small.bin").decoding(),
+            Decoding::Greedy { best_of: 5 }
+        );
+        assert_eq!(
+            settings_for("models/ggml-tiny.en.bin").decoding(),
+            Decoding::BeamSearch {
+                beam_size: 5,
+                patience: 1.0
+            }
+        );
+        assert_eq!(
+            settings_for("models/ggml-large-v3.bin").decoding(),
+            Decoding::Greedy { best_of: 1 }
+        );
+        assert_eq!(
+            settings_for("models/custom.bin").decoding(),
+            FALLBACK_DECODING
+        );
+    }
+
+    #[test]
+    fn explicit_decoding_overrides_model_default() {
+        let mut settings = settings_for("models/ggml-small.bin");
+        settings.beam_size = Some(3);
+        assert_eq!(
+            settings.decoding(),
+            Decoding::BeamSearch {
+                beam_size: 3,
+                patience: 1.0
+            }
+        );
+
+        let mut settings = settings_for("models/ggml-tiny.bin");
+        settings.best_of = Some(2);
+        assert_eq!(settings.decoding(), Decoding::Greedy { best_of: 2 });
+    }
+
+    #[test]
+    fn default_settings_leave_whisper_thresholds_untouched() {
+        let mut params = RecordedParams::default();
+        apply_decoding_thresholds(&mut params, &TranscriptionSettings::default());
+        assert!(params.temperature.is_none());
+        assert!(params.no_speech.is_none());
+        assert!(params.entropy.is_none());
+    }
+
+    #[test]
+    fn configured_thresholds_map_to_setters() {
+        let settings = TranscriptionSettings {
+            temperature: Some(0.2),
+            no_speech_threshold: Some(0.7),
+            entropy_threshold: Some(2.6),
+            ..TranscriptionSettings::default()
+        };
+        let mut params = RecordedParams::default();
+        apply_decoding_thresholds(&mut params, &settings);
+        assert_eq!(params.temperature, Some(0.2));
+        assert_eq!(params.no_speech, Some(0.7));
+        assert_eq!(params.entropy, Some(2.6));
+    }
+
+    #[test]
+    fn whisper_input_resamples_to_target_rate() {
+        let audio = AudioData {
+            samples: vec![0.0; 44_100],
+            sample_rate: 44_100,
+        };
+        let input = whisper_input(&audio, TARGET_SAMPLE_RATE);
+        assert_eq!(input.sample_rate, 16_000);
+        assert_eq!(input.samples.len(), 16_000);
+    }
+
+    #[test]
+    fn whisper_input_borrows_matching_rate() {
+        let audio = AudioData {
+            samples: vec![0.0; 160],
+            sample_rate: 16_000,
+        };
+        assert!(matches!(whisper_input(&audio, 16_000), Cow::Borrowed(_)));
+    }
+
     #[test]
     #[ignore] // Requires model file to be downloaded
     fn test_transcribe_audio() {
@@ -139,7 +570,11 @@ mod tests {
         };
 
         // This will fail without the model, but shows the API usage
-        let _result = transcribe_audio(&audio, &TranscriptionSettings::default());
+        let _result = transcribe_audio(
+            &audio,
+            &TranscriptionSettings::default(),
+            &CancelFlag::default(),
+        );
         // If model exists, verify we got a transcript
         // assert!(result.is_ok());
     }
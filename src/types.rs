@@ -1,7 +1,8 @@
 //! Core types for flowalyzer audio processing pipeline
 
 use anyhow::{ensure, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 /// Raw audio data representation (mono, f32 samples)
 #[derive(Debug, Clone)]
@@ -12,14 +13,36 @@ pub struct AudioData {
     pub sample_rate: u32,
 }
 
+impl AudioData {
+    /// Duration in seconds
+    pub fn duration(&self) -> f64 {
+        self.samples.len() as f64 / self.sample_rate as f64
+    }
+
+    /// Replace NaN/Inf samples with silence, returning how many were replaced
+    pub fn sanitize(&mut self) -> usize {
+        sanitize_samples(&mut self.samples)
+    }
+}
+
+/// Replace non-finite samples with 0.0 in place, returning how many were replaced
+pub fn sanitize_samples(samples: &mut [f32]) -> usize {
+    let mut replaced = 0;
+    for sample in samples.iter_mut().filter(|sample| !sample.is_finite()) {
+        *sample = 0.0;
+        replaced += 1;
+    }
+    replaced
+}
+
 /// Transcription output containing timestamped segments
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Transcript {
     pub segments: Vec<Segment>,
 }
 
 /// A segment of transcribed audio with timing information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Segment {
     pub text: String,
     pub start_time: f64, // seconds
@@ -28,9 +51,11 @@ pub struct Segment {
 }
 
 /// Granularity of a transcript segment
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Granularity {
     Word,
+    /// Mid-length unit between a word and a sentence, e.g. an intonation phrase
+    Phrase,
     Sentence,
 }
 
@@ -63,6 +88,17 @@ impl ChunkConfig {
     }
 }
 
+/// How chunk boundaries are chosen
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChunkStrategy {
+    /// Group transcript segments at pauses and phrase ends near the target duration
+    Linguistic,
+    /// Back-to-back slices of a fixed length, ignoring the transcript
+    Fixed { duration: f64 },
+    /// One chunk per spoken phrase, closing at each phrase- or sentence-length segment
+    Phrase,
+}
+
 /// An audio chunk with timing information
 #[derive(Debug, Clone)]
 pub struct AudioChunk {
@@ -72,6 +108,14 @@ pub struct AudioChunk {
     pub end_time: f64,
 }
 
+/// Two-channel audio, used by operations that place sound spatially
+#[derive(Debug, Clone)]
+pub struct StereoChunk {
+    pub left: Vec<f32>,
+    pub right: Vec<f32>,
+    pub sample_rate: u32,
+}
+
 /// A single step in a recipe: repeat N times at specific speed, optionally add silence after
 #[derive(Debug, Clone)]
 pub struct RecipeStep {
@@ -81,6 +125,98 @@ pub struct RecipeStep {
     pub speed_factor: f32,
     /// When true, emit silence chunks instead of audio
     pub silent: bool,
+    /// Silence inserted between consecutive repeats, in milliseconds
+    pub repeat_gap_ms: f64,
+    /// Follow each non-final repeat with a response gap as long as the repeat,
+    /// in place of `repeat_gap_ms`
+    pub interleave_silence: bool,
+}
+
+impl RecipeStep {
+    /// `repeat_count` back-to-back audio repeats at `speed_factor`
+    pub fn audio(repeat_count: u32, speed_factor: f32) -> Self {
+        Self {
+            repeat_count,
+            speed_factor,
+            silent: false,
+            repeat_gap_ms: 0.0,
+            interleave_silence: false,
+        }
+    }
+
+    /// `repeat_count` silences, each as long as the chunk at `speed_factor`
+    pub fn silence(repeat_count: u32, speed_factor: f32) -> Self {
+        Self {
+            silent: true,
+            ..Self::audio(repeat_count, speed_factor)
+        }
+    }
+}
+
+/// Slowest supported speed; lower factors would need absurdly large output buffers
+pub const MIN_SPEED_FACTOR: f32 = 0.1;
+
+/// Fastest supported speed
+pub const MAX_SPEED_FACTOR: f32 = 10.0;
+
+/// Algorithm used to change a chunk's speed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, clap::ValueEnum)]
+pub enum SpeedAlgorithm {
+    /// Signalsmith Stretch: pitch-preserving, highest quality, slowest
+    #[default]
+    Signalsmith,
+    /// Waveform-similarity overlap-add: pitch-preserving and faster, softer transients
+    Wsola,
+    /// Plain rate change: fastest, but pitch moves with speed
+    Resample,
+}
+
+/// Noise gate applied to each chunk before the recipe steps
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct NoiseGate {
+    /// Level below which audio is faded out, in dBFS
+    #[serde(alias = "thresholdDb")]
+    pub threshold_db: f32,
+    /// Time for the gate to open once the level rises above the threshold
+    #[serde(default = "default_gate_attack_ms", alias = "attackMs")]
+    pub attack_ms: f32,
+    /// Time for the gate to close once the level drops below the threshold
+    #[serde(default = "default_gate_release_ms", alias = "releaseMs")]
+    pub release_ms: f32,
+}
+
+fn default_gate_attack_ms() -> f32 {
+    5.0
+}
+
+fn default_gate_release_ms() -> f32 {
+    80.0
+}
+
+/// Stereo sweep across each rendered chunk, for dichotic-listening drills
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PanSweep {
+    /// Pan position at the start of the chunk (-1.0 = hard left, 1.0 = hard right)
+    pub start: f32,
+    /// Pan position at the end of the chunk
+    pub end: f32,
+}
+
+/// Audio laid quietly under every audio repeat, e.g. for shadowing decks
+#[derive(Debug, Clone)]
+pub struct Mix {
+    pub bed: MixBed,
+    /// Bed level relative to full scale, in dB
+    pub gain_db: f32,
+}
+
+/// Where a mix bed comes from
+#[derive(Debug, Clone)]
+pub enum MixBed {
+    /// The chunk itself, at its original speed
+    Chunk,
+    /// A decoded backing track aligned with the source's timeline
+    Track { audio: Arc<AudioData> },
 }
 
 /// A recipe is a sequence of steps to apply to each chunk
@@ -90,6 +226,21 @@ pub struct Recipe {
     pub name: String,
     /// Steps to apply in order
     pub steps: Vec<RecipeStep>,
+    /// RMS amplitude of comfort noise in silences and gaps (0.0 = pure silence)
+    pub silence_floor: f32,
+    /// How speed-adjusted steps are rendered
+    pub speed_algorithm: SpeedAlgorithm,
+    /// Gate applied to the chunk before any step, to drop low-level hiss
+    pub noise_gate: Option<NoiseGate>,
+    /// When set, silent steps last this multiple of the speaker's pause after the
+    /// chunk instead of the speed-adjusted chunk length
+    pub natural_pause_scale: Option<f64>,
+    /// Length of the ramp at each edge of inserted silences, in milliseconds (0.0 = hard cut)
+    pub silence_fade_ms: f64,
+    /// Bed mixed under every audio repeat
+    pub mix: Option<Mix>,
+    /// Sweep across the stereo field applied to each rendered chunk, making its file stereo
+    pub pan: Option<PanSweep>,
 }
 
 impl Recipe {
@@ -98,6 +249,13 @@ impl Recipe {
         Self {
             name: name.into(),
             steps: Vec::new(),
+            silence_floor: 0.0,
+            speed_algorithm: SpeedAlgorithm::default(),
+            noise_gate: None,
+            natural_pause_scale: None,
+            silence_fade_ms: 0.0,
+            mix: None,
+            pan: None,
         }
     }
 
@@ -106,15 +264,29 @@ impl Recipe {
         self.steps.push(step);
         self
     }
+
+    /// Silent-step length in seconds for a chunk followed by `natural_pause`, or `None`
+    /// when silences should keep matching the speed-adjusted chunk
+    pub fn natural_silence(&self, natural_pause: Option<f64>) -> Option<f64> {
+        Some(self.natural_pause_scale? * natural_pause?)
+    }
 }
 
 /// Runtime-configurable recipe parsed from JSON input
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuntimeRecipe {
     #[serde(default)]
     pub name: Option<String>,
     #[serde(default)]
     pub steps: Vec<RuntimeRecipeStep>,
+    #[serde(default, alias = "silenceFloor")]
+    pub silence_floor: f32,
+    #[serde(default, alias = "noiseGate")]
+    pub noise_gate: Option<NoiseGate>,
+    #[serde(default, alias = "silenceFadeMs")]
+    pub silence_fade_ms: f64,
+    #[serde(default)]
+    pub pan: Option<PanSweep>,
 }
 
 impl RuntimeRecipe {
@@ -126,6 +298,34 @@ impl RuntimeRecipe {
         for (idx, step) in self.steps.iter().enumerate() {
             step.validate(idx)?;
         }
+        ensure!(
+            self.steps.iter().any(|step| !step.silent),
+            "Recipe steps are all silent, so every chunk would render as pure silence"
+        );
+        ensure!(
+            (0.0..=1.0).contains(&self.silence_floor),
+            "Recipe silence_floor must be between 0.0 and 1.0"
+        );
+        ensure!(
+            self.silence_fade_ms >= 0.0,
+            "Recipe silence_fade_ms must not be negative"
+        );
+        if let Some(gate) = &self.noise_gate {
+            ensure!(
+                gate.threshold_db <= 0.0,
+                "Recipe noise_gate threshold_db must be at or below 0 dBFS"
+            );
+            ensure!(
+                gate.attack_ms >= 0.0 && gate.release_ms >= 0.0,
+                "Recipe noise_gate attack_ms and release_ms must not be negative"
+            );
+        }
+        if let Some(pan) = &self.pan {
+            ensure!(
+                (-1.0..=1.0).contains(&pan.start) && (-1.0..=1.0).contains(&pan.end),
+                "Recipe pan start and end must be between -1.0 and 1.0"
+            );
+        }
         Ok(())
     }
 
@@ -134,12 +334,16 @@ impl RuntimeRecipe {
         for step in &self.steps {
             recipe = recipe.add_step(step.to_recipe_step());
         }
+        recipe.silence_floor = self.silence_floor;
+        recipe.noise_gate = self.noise_gate;
+        recipe.silence_fade_ms = self.silence_fade_ms;
+        recipe.pan = self.pan;
         recipe
     }
 }
 
 /// Runtime-configurable recipe step parsed from JSON
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RuntimeRecipeStep {
     #[serde(alias = "repeat", alias = "repeatCount")]
     pub repeat_count: u32,
@@ -147,6 +351,10 @@ pub struct RuntimeRecipeStep {
     pub speed_factor: f32,
     #[serde(default, alias = "silent")]
     pub silent: bool,
+    #[serde(default, alias = "gap_ms", alias = "repeatGapMs")]
+    pub repeat_gap_ms: f64,
+    #[serde(default, alias = "interleave", alias = "interleaveSilence")]
+    pub interleave_silence: bool,
 }
 
 impl RuntimeRecipeStep {
@@ -157,18 +365,157 @@ impl RuntimeRecipeStep {
             index
         );
         ensure!(
-            self.speed_factor > 0.0,
-            "Recipe step {} speed_factor must be positive",
+            (MIN_SPEED_FACTOR..=MAX_SPEED_FACTOR).contains(&self.speed_factor),
+            "Recipe step {} speed_factor must be between {} and {}, got {}",
+            index,
+            MIN_SPEED_FACTOR,
+            MAX_SPEED_FACTOR,
+            self.speed_factor
+        );
+        ensure!(
+            self.repeat_gap_ms >= 0.0,
+            "Recipe step {} repeat_gap_ms must not be negative",
+            index
+        );
+        ensure!(
+            !(self.silent && self.interleave_silence),
+            "Recipe step {} cannot interleave silence into a silent step",
             index
         );
         Ok(())
     }
 
     fn to_recipe_step(&self) -> RecipeStep {
+        let step = if self.silent {
+            RecipeStep::silence(self.repeat_count, self.speed_factor)
+        } else {
+            RecipeStep::audio(self.repeat_count, self.speed_factor)
+        };
         RecipeStep {
-            repeat_count: self.repeat_count,
-            speed_factor: self.speed_factor,
-            silent: self.silent,
+            repeat_gap_ms: self.repeat_gap_ms,
+            interleave_silence: self.interleave_silence,
+            ..step
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitize_replaces_non_finite_samples() {
+        let mut audio = AudioData {
+            samples: vec![0.5, f32::NAN, -0.25, f32::INFINITY, f32::NEG_INFINITY],
+            sample_rate: 16_000,
+        };
+        let replaced = audio.sanitize();
+        assert_eq!(replaced, 3);
+        assert_eq!(audio.samples, vec![0.5, 0.0, -0.25, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn sanitize_leaves_finite_audio_untouched() {
+        let mut audio = AudioData {
+            samples: vec![0.1, -0.1],
+            sample_rate: 16_000,
+        };
+        assert_eq!(audio.sanitize(), 0);
+        assert_eq!(audio.samples, vec![0.1, -0.1]);
+    }
+
+    #[test]
+    fn recipe_rejects_out_of_range_speed_factors() {
+        let recipe = |speed_factor: f32| RuntimeRecipe {
+            name: None,
+            steps: vec![RuntimeRecipeStep {
+                repeat_count: 1,
+                speed_factor,
+                silent: false,
+                repeat_gap_ms: 0.0,
+                interleave_silence: false,
+            }],
+            silence_floor: 0.0,
+            noise_gate: None,
+            silence_fade_ms: 0.0,
+            pan: None,
+        };
+        assert!(recipe(0.001).validate().is_err());
+        assert!(recipe(f32::NAN).validate().is_err());
+        assert!(recipe(50.0).validate().is_err());
+        assert!(recipe(0.5).validate().is_ok());
+    }
+
+    #[test]
+    fn recipe_pan_parses_and_rejects_positions_off_the_field() {
+        let json = r#"{"steps": [{"repeat_count": 1, "speed_factor": 1.0}],
+                       "pan": {"start": -1.0, "end": 1.0}}"#;
+        let mut recipe: RuntimeRecipe = serde_json::from_str(json).unwrap();
+        assert!(recipe.validate().is_ok());
+        assert_eq!(
+            recipe.to_recipe().pan,
+            Some(PanSweep {
+                start: -1.0,
+                end: 1.0
+            })
+        );
+
+        recipe.pan = Some(PanSweep {
+            start: -1.5,
+            end: 1.0,
+        });
+        assert!(recipe.validate().is_err());
+    }
+
+    #[test]
+    fn recipe_with_only_silent_steps_is_rejected() {
+        let step = |silent: bool| RuntimeRecipeStep {
+            repeat_count: 1,
+            speed_factor: 1.0,
+            silent,
+            repeat_gap_ms: 0.0,
+            interleave_silence: false,
+        };
+        let recipe = |steps: Vec<RuntimeRecipeStep>| RuntimeRecipe {
+            name: None,
+            steps,
+            silence_floor: 0.0,
+            noise_gate: None,
+            silence_fade_ms: 0.0,
+            pan: None,
+        };
+
+        let all_silent = recipe(vec![step(true), step(true)]).validate();
+        assert!(all_silent.unwrap_err().to_string().contains("all silent"));
+        assert!(recipe(vec![step(false), step(true)]).validate().is_ok());
+    }
+
+    #[test]
+    fn interleaving_a_silent_step_is_rejected() {
+        let recipe = RuntimeRecipe {
+            name: None,
+            steps: vec![
+                RuntimeRecipeStep {
+                    repeat_count: 1,
+                    speed_factor: 1.0,
+                    silent: false,
+                    repeat_gap_ms: 0.0,
+                    interleave_silence: false,
+                },
+                RuntimeRecipeStep {
+                    repeat_count: 1,
+                    speed_factor: 1.0,
+                    silent: true,
+                    repeat_gap_ms: 0.0,
+                    interleave_silence: true,
+                },
+            ],
+            silence_floor: 0.0,
+            noise_gate: None,
+            silence_fade_ms: 0.0,
+            pan: None,
+        };
+        let err = recipe.validate().unwrap_err().to_string();
+        assert!(err.contains("step 1 cannot interleave"), "{}", err);
+    }
+}